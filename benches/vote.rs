@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
-use voting_map::{election, Point};
+use voting_map::{election, Hare, MethodRegistry, Plurality, Point, Settings};
 
 fn criterion_benchmark(c: &mut Criterion) {
     let candidates = vec![
@@ -10,12 +10,75 @@ fn criterion_benchmark(c: &mut Criterion) {
     ];
 
     let mut group = c.benchmark_group("election");
-    for size in [128].iter() {
+    for size in [64, 128].iter() {
         group.bench_with_input(BenchmarkId::from_parameter(size), size, |b, &size| {
-            b.iter(|| election(size, &candidates, "hare"))
+            b.iter(|| election(black_box(size), &candidates, &Hare, &Settings::default()).unwrap())
         });
     }
 }
 
-criterion_group!(benches, criterion_benchmark);
+fn ten_candidates_benchmark(c: &mut Criterion) {
+    let candidates = vec![
+        Point::new(0.15, 0.2),
+        Point::new(0.3, 0.85),
+        Point::new(0.5, 0.5),
+        Point::new(0.85, 0.3),
+        Point::new(0.7, 0.75),
+        Point::new(0.45, 0.1),
+        Point::new(0.05, 0.6),
+        Point::new(0.95, 0.9),
+        Point::new(0.6, 0.35),
+        Point::new(0.35, 0.55),
+    ];
+
+    let mut group = c.benchmark_group("ten_candidates");
+    group.sample_size(10);
+    group.bench_function("plurality", |b| {
+        b.iter(|| {
+            election(
+                black_box(128),
+                &candidates,
+                &Plurality,
+                &Settings::default(),
+            )
+            .unwrap()
+        })
+    });
+    group.bench_function("hare", |b| {
+        b.iter(|| election(black_box(64), &candidates, &Hare, &Settings::default()).unwrap())
+    });
+}
+
+/// Every method on the same map, to compare against a baseline saved with the `scalar` feature:
+/// `cargo bench --features scalar -- --save-baseline scalar methods`, then
+/// `cargo bench -- --baseline scalar methods`.
+fn methods_benchmark(c: &mut Criterion) {
+    let candidates = vec![
+        Point::new(0.15, 0.2),
+        Point::new(0.3, 0.85),
+        Point::new(0.5, 0.5),
+        Point::new(0.85, 0.3),
+        Point::new(0.7, 0.75),
+        Point::new(0.45, 0.1),
+        Point::new(0.05, 0.6),
+        Point::new(0.95, 0.9),
+        Point::new(0.6, 0.35),
+        Point::new(0.35, 0.55),
+    ];
+
+    let mut group = c.benchmark_group("methods");
+    group.sample_size(10);
+    for method in MethodRegistry::default().iter() {
+        group.bench_function(method.id(), |b| {
+            b.iter(|| election(black_box(96), &candidates, method, &Settings::default()).unwrap())
+        });
+    }
+}
+
+criterion_group!(
+    benches,
+    criterion_benchmark,
+    ten_candidates_benchmark,
+    methods_benchmark
+);
 criterion_main!(benches);
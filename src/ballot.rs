@@ -0,0 +1,302 @@
+//! Typed ballots and how they are stored.
+
+use crate::simd;
+
+/// The kind of ballot an election method asks voters to fill in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BallotType {
+    /// A mark for at most one candidate.
+    Single,
+    /// Candidates ordered by preference. Several candidates may share a rank, and candidates can be
+    /// left unranked.
+    Ranking,
+    /// A yes / no mark for every candidate.
+    Approval,
+}
+
+impl BallotType {
+    /// Stable identifier, used by the web page.
+    pub fn id(self) -> &'static str {
+        match self {
+            BallotType::Single => "single",
+            BallotType::Ranking => "ranking",
+            BallotType::Approval => "approval",
+        }
+    }
+}
+
+/// Read-only view of a single ballot of a known type.
+///
+/// Ballots are stored as one byte per candidate; the `Ballot` implementations are the only code
+/// that interprets those bytes.
+pub trait Ballot<'a>: Copy {
+    const TYPE: BallotType;
+
+    fn from_raw(raw: &'a [u8]) -> Self;
+}
+
+/// Ballot marking a single candidate, or none if the voter abstains.
+#[derive(Copy, Clone, Debug)]
+pub struct Single<T>(T);
+
+/// A ranked ballot; rank 1 is the most preferred candidate.
+///
+/// Several candidates may share a rank. Candidates that the voter didn't rank at all are preferred
+/// less than every ranked candidate.
+#[derive(Copy, Clone, Debug)]
+pub struct Ranking<T>(T);
+
+/// Approval ballot, marking every candidate as approved or not.
+#[derive(Copy, Clone, Debug)]
+pub struct Approval<T>(T);
+
+const UNRANKED: u8 = 0;
+
+impl<T: AsRef<[u8]>> Single<T> {
+    /// The marked candidate, `None` if the voter abstained.
+    pub fn choice(&self) -> Option<usize> {
+        self.0.as_ref().iter().position(|&mark| mark != 0)
+    }
+
+    /// Adds `weight` to the votes of the marked candidate.
+    pub fn count_choice(&self, votes: &mut [f32], weight: f32) {
+        if let Some(c) = self.choice() {
+            votes[c] += weight;
+        }
+    }
+}
+
+impl<T: AsMut<[u8]>> Single<T> {
+    /// Marks the candidate, and no other.
+    pub fn choose(&mut self, candidate: usize) {
+        for (c, mark) in self.0.as_mut().iter_mut().enumerate() {
+            *mark = (c == candidate) as u8;
+        }
+    }
+
+    /// Leaves the ballot blank.
+    pub fn abstain(&mut self) {
+        self.0.as_mut().fill(0);
+    }
+}
+
+impl<T: AsRef<[u8]>> Ranking<T> {
+    pub fn num_candidates(&self) -> usize {
+        self.0.as_ref().len()
+    }
+
+    /// Rank of the candidate, `None` if the voter didn't rank it.
+    pub fn rank(&self, candidate: usize) -> Option<u8> {
+        match self.0.as_ref()[candidate] {
+            UNRANKED => None,
+            rank => Some(rank),
+        }
+    }
+
+    /// Whether the voter strictly prefers `a` over `b`.
+    pub fn prefers(&self, a: usize, b: usize) -> bool {
+        match (self.rank(a), self.rank(b)) {
+            (Some(a), Some(b)) => a < b,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+
+    /// The best rank given to any candidate that isn't set in the `excluded` bitmask.
+    fn best_rank(&self, excluded: u64) -> Option<u8> {
+        self.0
+            .as_ref()
+            .iter()
+            .enumerate()
+            .filter(|&(c, &rank)| rank != UNRANKED && (1 << c) & excluded == 0)
+            .map(|(_, &rank)| rank)
+            .min()
+    }
+
+    /// The most preferred candidates among those not set in the `excluded` bitmask.
+    ///
+    /// Yields several candidates if they share the top rank, and none once all ranked candidates
+    /// are excluded, i.e. the ballot is exhausted.
+    pub fn top_choices(&self, excluded: u64) -> impl Iterator<Item = usize> + '_ {
+        let best = self.best_rank(excluded);
+        self.0
+            .as_ref()
+            .iter()
+            .enumerate()
+            .filter(move |&(c, &rank)| Some(rank) == best && (1 << c) & excluded == 0)
+            .map(|(c, _)| c)
+    }
+
+    /// Adds `weight` to the votes of the most preferred candidates that aren't excluded, split
+    /// evenly between candidates of equal rank.
+    pub fn count_top_choices(&self, votes: &mut [f32], excluded: u64, weight: f32) {
+        let mut top_choices = self.top_choices(excluded);
+        let first = match top_choices.next() {
+            Some(c) => c,
+            None => return,
+        };
+        let num_top = 1 + top_choices.count();
+        if num_top == 1 {
+            votes[first] += weight;
+        } else {
+            for c in self.top_choices(excluded) {
+                votes[c] += weight / num_top as f32;
+            }
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> Ranking<T> {
+    /// Adds `weight` to the votes of every candidate that the voter prefers `candidate` to.
+    pub fn count_below(&self, votes: &mut [f32], candidate: usize, weight: f32) {
+        if let Some(rank) = self.rank(candidate) {
+            simd::add_ranked_below(votes, self.0.as_ref(), rank, weight);
+        }
+    }
+
+    /// Adds the Borda points of every candidate times `weight` to its votes: a candidate gets one
+    /// point for every rank below its own, and unranked candidates none.
+    pub fn count_borda(&self, votes: &mut [f32], weight: f32) {
+        simd::add_borda(votes, self.0.as_ref(), weight);
+    }
+}
+
+impl<T: AsMut<[u8]>> Ranking<T> {
+    /// Gives the candidate a rank, starting at 1 for the most preferred candidate.
+    pub fn set_rank(&mut self, candidate: usize, rank: u8) {
+        assert!(rank != UNRANKED, "ranks start at 1");
+        self.0.as_mut()[candidate] = rank;
+    }
+
+    /// Leaves the candidate off the ballot.
+    pub fn unrank(&mut self, candidate: usize) {
+        self.0.as_mut()[candidate] = UNRANKED;
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Ranking<T> {
+    /// Moves the candidate to the top of the ranking, alone, keeping the order of the others.
+    pub fn promote(&mut self, candidate: usize) {
+        let old = self.rank(candidate).unwrap_or(u8::MAX);
+        for (c, rank) in self.0.as_mut().iter_mut().enumerate() {
+            if c != candidate && *rank != UNRANKED && *rank <= old {
+                *rank += 1;
+            }
+        }
+        self.0.as_mut()[candidate] = 1;
+    }
+
+    /// Moves a ranked candidate to the bottom of the ranking, alone, keeping the order of the
+    /// others. Unranked candidates are already at the bottom.
+    pub fn demote(&mut self, candidate: usize) {
+        let old = match self.rank(candidate) {
+            Some(old) => old,
+            None => return,
+        };
+        // A rank is one more than the number of candidates ranked above, which no longer counts
+        // the demoted candidate for those it was ahead of.
+        let ranks = self.0.as_mut();
+        let mut num_ranked = 0;
+        for (c, rank) in ranks.iter_mut().enumerate() {
+            if c != candidate && *rank != UNRANKED {
+                num_ranked += 1;
+                if *rank > old {
+                    *rank -= 1;
+                }
+            }
+        }
+        ranks[candidate] = num_ranked + 1;
+    }
+}
+
+impl<T: AsRef<[u8]>> Approval<T> {
+    pub fn approves(&self, candidate: usize) -> bool {
+        self.0.as_ref()[candidate] != 0
+    }
+
+    /// Adds `weight` to the votes of every approved candidate.
+    pub fn count_approvals(&self, votes: &mut [f32], weight: f32) {
+        simd::add_approved(votes, self.0.as_ref(), weight);
+    }
+}
+
+impl<T: AsMut<[u8]>> Approval<T> {
+    pub fn approve(&mut self, candidate: usize) {
+        self.0.as_mut()[candidate] = 1;
+    }
+
+    pub fn disapprove(&mut self, candidate: usize) {
+        self.0.as_mut()[candidate] = 0;
+    }
+}
+
+impl<'a> Ballot<'a> for Single<&'a [u8]> {
+    const TYPE: BallotType = BallotType::Single;
+
+    fn from_raw(raw: &'a [u8]) -> Self {
+        Single(raw)
+    }
+}
+
+impl<'a> Ballot<'a> for Ranking<&'a [u8]> {
+    const TYPE: BallotType = BallotType::Ranking;
+
+    fn from_raw(raw: &'a [u8]) -> Self {
+        Ranking(raw)
+    }
+}
+
+impl<'a> Ballot<'a> for Approval<&'a [u8]> {
+    const TYPE: BallotType = BallotType::Approval;
+
+    fn from_raw(raw: &'a [u8]) -> Self {
+        Approval(raw)
+    }
+}
+
+/// A blank ballot for a voter to fill in.
+#[derive(Debug)]
+pub enum BallotMut<'a> {
+    Single(Single<&'a mut [u8]>),
+    Ranking(Ranking<&'a mut [u8]>),
+    Approval(Approval<&'a mut [u8]>),
+}
+
+impl<'a> BallotMut<'a> {
+    pub(crate) fn new(ballot_type: BallotType, raw: &'a mut [u8]) -> BallotMut<'a> {
+        match ballot_type {
+            BallotType::Single => BallotMut::Single(Single(raw)),
+            BallotType::Ranking => BallotMut::Ranking(Ranking(raw)),
+            BallotType::Approval => BallotMut::Approval(Approval(raw)),
+        }
+    }
+
+    pub fn ballot_type(&self) -> BallotType {
+        match self {
+            BallotMut::Single(_) => BallotType::Single,
+            BallotMut::Ranking(_) => BallotType::Ranking,
+            BallotMut::Approval(_) => BallotType::Approval,
+        }
+    }
+
+    pub fn into_single(self) -> Single<&'a mut [u8]> {
+        match self {
+            BallotMut::Single(b) => b,
+            b => panic!("expected a single mark ballot, got {:?}", b.ballot_type()),
+        }
+    }
+
+    pub fn into_ranking(self) -> Ranking<&'a mut [u8]> {
+        match self {
+            BallotMut::Ranking(b) => b,
+            b => panic!("expected a ranked ballot, got {:?}", b.ballot_type()),
+        }
+    }
+
+    pub fn into_approval(self) -> Approval<&'a mut [u8]> {
+        match self {
+            BallotMut::Approval(b) => b,
+            b => panic!("expected an approval ballot, got {:?}", b.ballot_type()),
+        }
+    }
+}
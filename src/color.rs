@@ -0,0 +1,138 @@
+//! Colors used to draw the vote map.
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const RED: Color = Color { r: 255, g: 0, b: 0 };
+    pub const GREEN: Color = Color { r: 0, g: 255, b: 0 };
+    pub const BLUE: Color = Color { r: 0, g: 0, b: 255 };
+    pub const YELLOW: Color = Color {
+        r: 255,
+        g: 255,
+        b: 0,
+    };
+    pub const PINK: Color = Color {
+        r: 255,
+        g: 20,
+        b: 147,
+    };
+    pub const WHITE: Color = Color {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+    /// Marks points where the election was tied.
+    pub const TIE: Color = Color { r: 0, g: 0, b: 0 };
+
+    /// Blends `self` towards `other`, by `t` between 0 and 1.
+    pub fn mix(self, other: Color, t: f32) -> Color {
+        let blend = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Color {
+            r: blend(self.r, other.r),
+            g: blend(self.g, other.g),
+            b: blend(self.b, other.b),
+        }
+    }
+
+    /// Converts from the OKLab color space, clamping colors outside of the sRGB gamut.
+    fn from_oklab(lab: [f32; 3]) -> Color {
+        let [l, a, b] = lab;
+        let l_ = (l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m_ = (l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s_ = (l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+
+        let to_srgb = |linear: f32| {
+            let v = if linear <= 0.003_130_8 {
+                12.92 * linear
+            } else {
+                1.055 * linear.powf(1.0 / 2.4) - 0.055
+            };
+            (v.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        Color {
+            r: to_srgb(4.076_741_7 * l_ - 3.307_711_6 * m_ + 0.230_969_94 * s_),
+            g: to_srgb(-1.268_438 * l_ + 2.609_757_4 * m_ - 0.341_319_38 * s_),
+            b: to_srgb(-0.004_196_086_3 * l_ - 0.703_418_6 * m_ + 1.707_614_7 * s_),
+        }
+    }
+
+    /// Converts to the OKLab color space, in which euclidean distance approximates perceived
+    /// difference.
+    fn to_oklab(self) -> [f32; 3] {
+        let to_linear = |c: u8| {
+            let v = c as f32 / 255.0;
+            if v <= 0.040_45 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (to_linear(self.r), to_linear(self.g), to_linear(self.b));
+
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        [
+            0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        ]
+    }
+}
+
+/// The colors of the first candidates, matching http://zesty.ca/voting/sim/.
+pub const CANDIDATE_COLORS: [Color; 5] = [
+    Color::RED,
+    Color::GREEN,
+    Color::BLUE,
+    Color::YELLOW,
+    Color::PINK,
+];
+
+/// Returns the colors of the first `n` candidates.
+///
+/// Starts with `CANDIDATE_COLORS`, then repeatedly adds the color that is perceptually furthest
+/// from all colors picked so far. The colors of existing candidates don't change when more
+/// candidates are added.
+pub fn palette(n: usize) -> Vec<Color> {
+    let mut colors: Vec<Color> = CANDIDATE_COLORS.iter().take(n).cloned().collect();
+    if n <= colors.len() {
+        return colors;
+    }
+
+    // Candidate colors, spread over lightness and hue. Very light colors are left out so that
+    // they remain distinguishable from the white background.
+    let mut options = vec![];
+    for &lightness in [0.45, 0.6, 0.75].iter() {
+        for &chroma in [0.1, 0.2].iter() {
+            for step in 0..24 {
+                let hue = step as f32 / 24.0 * 2.0 * std::f32::consts::PI;
+                let color = Color::from_oklab([lightness, chroma * hue.cos(), chroma * hue.sin()]);
+                options.push((color, color.to_oklab()));
+            }
+        }
+    }
+
+    let mut picked: Vec<[f32; 3]> = colors.iter().map(|c| c.to_oklab()).collect();
+    while colors.len() < n {
+        let (color, lab, _) = options
+            .iter()
+            .map(|&(color, lab)| {
+                let closest = picked
+                    .iter()
+                    .map(|p| (0..3).map(|i| (p[i] - lab[i]).powi(2)).sum::<f32>())
+                    .fold(f32::MAX, f32::min);
+                (color, lab, closest)
+            })
+            .max_by(|a, b| a.2.partial_cmp(&b.2).unwrap())
+            .unwrap();
+        colors.push(color);
+        picked.push(lab);
+    }
+    colors
+}
@@ -0,0 +1,471 @@
+//! The distribution of voters around each point of the map.
+
+use std::str::FromStr;
+
+use crate::{Point, MAX_SPREAD};
+
+/// Spread of a cluster of voters: the standard deviation along each axis, and the correlation
+/// between the two axes.
+///
+/// Together they define the 2x2 covariance matrix of the cluster, see `matrix`. Cauchy clusters
+/// have no standard deviation, and use `sigma_x` and `sigma_y` as their scale instead.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Covariance {
+    /// Standard deviation along the x axis, in map units.
+    pub sigma_x: f32,
+    /// Standard deviation along the y axis, in map units.
+    pub sigma_y: f32,
+    /// Correlation between the positions of voters along both axes, strictly between -1 and 1.
+    pub correlation: f32,
+}
+
+impl Covariance {
+    /// The same spread `sigma` in every direction.
+    pub const fn isotropic(sigma: f32) -> Covariance {
+        Covariance {
+            sigma_x: sigma,
+            sigma_y: sigma,
+            correlation: 0.0,
+        }
+    }
+
+    /// Converts from a covariance matrix. Only the upper triangle of the matrix is used.
+    pub fn from_matrix(matrix: [[f32; 2]; 2]) -> Covariance {
+        let sigma_x = matrix[0][0].sqrt();
+        let sigma_y = matrix[1][1].sqrt();
+        Covariance {
+            sigma_x,
+            sigma_y,
+            correlation: matrix[0][1] / (sigma_x * sigma_y),
+        }
+    }
+
+    pub fn matrix(&self) -> [[f32; 2]; 2] {
+        let xy = self.correlation * self.sigma_x * self.sigma_y;
+        [[self.sigma_x.powi(2), xy], [xy, self.sigma_y.powi(2)]]
+    }
+
+    /// Whether the axes of the electorate are aligned with the axes of the map.
+    pub fn is_axis_aligned(&self) -> bool {
+        self.correlation == 0.0
+    }
+}
+
+/// How the density of voters falls off away from the center of a cluster.
+///
+/// Shapes with a standard deviation are scaled so that it matches the `Covariance` of the cluster.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Shape {
+    #[default]
+    Gaussian,
+    /// Uniform inside a square, or a parallelogram for correlated clusters.
+    Square,
+    /// Uniform inside a disc, or an ellipse for anisotropic clusters.
+    Disc,
+    /// Falls off exponentially along each axis.
+    Laplace,
+    /// Heavy tailed: falls off with the square of the distance along each axis.
+    Cauchy,
+}
+
+impl FromStr for Shape {
+    type Err = String;
+
+    /// Parses `gaussian`, `square`, `disc`, `laplace` or `cauchy`.
+    fn from_str(s: &str) -> Result<Shape, String> {
+        match s {
+            "gaussian" => Ok(Shape::Gaussian),
+            "square" => Ok(Shape::Square),
+            "disc" => Ok(Shape::Disc),
+            "laplace" => Ok(Shape::Laplace),
+            "cauchy" => Ok(Shape::Cauchy),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+impl Shape {
+    /// How far voters reach from the center, in standard deviations, if not infinitely far.
+    fn support(self) -> Option<f32> {
+        match self {
+            Shape::Square => Some(3f32.sqrt()),
+            Shape::Disc => Some(2.0),
+            Shape::Gaussian | Shape::Laplace | Shape::Cauchy => None,
+        }
+    }
+
+    /// How far from the center voters are simulated, in standard deviations, when `num_sigma` is
+    /// asked for. Heavy tails hold more of the cluster beyond `num_sigma`, so they reach further.
+    fn reach(self, num_sigma: f32) -> f32 {
+        match self {
+            Shape::Gaussian => num_sigma,
+            Shape::Square | Shape::Disc => self.support().unwrap().min(num_sigma),
+            Shape::Laplace => 2.0 * num_sigma,
+            Shape::Cauchy => 4.0 * num_sigma,
+        }
+    }
+
+    /// Density of voters at `u` standard deviations from the center along one axis, for shapes
+    /// that are a product of their densities along both axes.
+    fn density(self, u: f32) -> f32 {
+        match self {
+            Shape::Gaussian => normal_pdf(0.0, 1.0, u),
+            Shape::Square if u.abs() <= 3f32.sqrt() => 1.0 / (2.0 * 3f32.sqrt()),
+            Shape::Laplace => (-2f32.sqrt() * u.abs()).exp() / 2f32.sqrt(),
+            Shape::Cauchy => 1.0 / (std::f32::consts::PI * (1.0 + u * u)),
+            Shape::Square | Shape::Disc => 0.0,
+        }
+    }
+
+    /// Density of voters at `(u, v)` standard deviations from the center.
+    fn density_2d(self, u: f32, v: f32) -> f32 {
+        match self {
+            Shape::Disc if u * u + v * v <= 4.0 => 1.0 / (4.0 * std::f32::consts::PI),
+            Shape::Disc => 0.0,
+            _ => self.density(u) * self.density(v),
+        }
+    }
+}
+
+/// A cluster of voters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cluster {
+    /// Relative size of the cluster; the weights of all clusters are scaled to sum to 1.
+    pub weight: f32,
+    /// Center of the cluster relative to the point of the map, in map units.
+    pub offset: Point,
+    pub shape: Shape,
+    pub covariance: Covariance,
+}
+
+impl Cluster {
+    /// How far the kernel of the cluster reaches from its center along either axis, in map
+    /// units, for every standard deviation it keeps.
+    ///
+    /// Correlated Gaussian clusters are summed along sheared lines, which reach further along x
+    /// than their standard deviation, up to `sqrt(2)` times. Other kernels that aren't separable
+    /// cover the box around a circle of voters, which reaches `sqrt(2)` times as far.
+    pub(crate) fn reach_per_sigma(&self) -> f32 {
+        let Covariance {
+            sigma_x,
+            sigma_y,
+            correlation,
+        } = self.covariance;
+        match self.shape {
+            Shape::Gaussian => {
+                let shear = (1.0 - correlation.powi(2)).sqrt() + correlation.abs();
+                (sigma_x * shear).max(sigma_y)
+            }
+            Shape::Disc => 2f32.sqrt() * sigma_x.max(sigma_y),
+            _ if !self.covariance.is_axis_aligned() => 2f32.sqrt() * sigma_x.max(sigma_y),
+            _ => sigma_x.max(sigma_y),
+        }
+    }
+}
+
+/// The voters around each point of the map, as a weighted mixture of clusters.
+///
+/// The map shows who wins as the whole mixture is moved around.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Electorate {
+    pub clusters: Vec<Cluster>,
+}
+
+impl Electorate {
+    /// A single Gaussian cluster, centered on the point of the map.
+    pub fn gaussian(covariance: Covariance) -> Electorate {
+        Electorate::single(Shape::Gaussian, covariance)
+    }
+
+    /// A single cluster, centered on the point of the map.
+    pub fn single(shape: Shape, covariance: Covariance) -> Electorate {
+        Electorate {
+            clusters: vec![Cluster {
+                weight: 1.0,
+                offset: Point::new(0.0, 0.0),
+                shape,
+                covariance,
+            }],
+        }
+    }
+
+    /// Two Gaussian clusters of the same size and shape, `separation` apart and centered on the
+    /// point of the map.
+    pub fn bimodal(covariance: Covariance, separation: Point) -> Electorate {
+        let half = Point::new(separation.x / 2.0, separation.y / 2.0);
+        let cluster = |offset| Cluster {
+            weight: 1.0,
+            offset,
+            shape: Shape::Gaussian,
+            covariance,
+        };
+        Electorate {
+            clusters: vec![cluster(Point::new(-half.x, -half.y)), cluster(half)],
+        }
+    }
+
+    /// The same electorate with the clusters `factor` times further from the point of the map,
+    /// making it more polarized for factors above 1 and less below.
+    pub fn polarized(&self, factor: f32) -> Electorate {
+        Electorate {
+            clusters: self
+                .clusters
+                .iter()
+                .map(|cluster| Cluster {
+                    offset: Point::new(cluster.offset.x * factor, cluster.offset.y * factor),
+                    ..cluster.clone()
+                })
+                .collect(),
+        }
+    }
+
+    /// Sum of the weights of all clusters.
+    pub fn total_weight(&self) -> f32 {
+        self.clusters.iter().map(|cluster| cluster.weight).sum()
+    }
+}
+
+pub(crate) fn normal_pdf(mean: f32, sigma: f32, x: f32) -> f32 {
+    1f32 / (sigma * (2f32 * std::f32::consts::PI).sqrt())
+        * (-1f32 / 2f32 * ((x - mean) / sigma).powi(2)).exp()
+}
+
+/// Weights of the voters of a cluster around a point, in pixels.
+#[derive(Clone)]
+pub(crate) struct Kernel {
+    pub weights: Weights,
+    /// Center of the cluster relative to the point, in pixels.
+    pub offset: (i32, i32),
+    /// How far from a point voters count, along either axis.
+    pub range: i32,
+}
+
+#[derive(Clone)]
+pub(crate) enum Weights {
+    /// Weights applied as two one dimensional passes.
+    ///
+    /// A Gaussian factors into the distribution along y, times the distribution along x given
+    /// the position along y. So the `line` pass first sums voters along x, with the spread along
+    /// x that remains once y is known. The `column` pass then adds up these sums along y,
+    /// shifting each by the mean x offset at that y. Axis-aligned clusters have no shift, which
+    /// makes this a plain separable blur.
+    Separable {
+        /// `(dx, weight)` of the voters summed along x.
+        line: Vec<(i32, f32)>,
+        /// `(dy, dx, weight)` of the line sums added up along y, and their shift along x.
+        column: Vec<(i32, i32, f32)>,
+        /// The largest shift along x of any line sum.
+        shear: i32,
+    },
+    /// `(dx, dy, weight)` of every voter around the point that has a weight.
+    Full(Vec<(i32, i32, f32)>),
+}
+
+impl Weights {
+    fn total(&self) -> f32 {
+        match self {
+            Weights::Separable { line, column, .. } => {
+                let line: f32 = line.iter().map(|&(_, p)| p).sum();
+                let column: f32 = column.iter().map(|&(_, _, p)| p).sum();
+                line * column
+            }
+            Weights::Full(weights) => weights.iter().map(|&(_, _, p)| p).sum(),
+        }
+    }
+
+    /// Scales the weights so that they add up to `total`.
+    fn normalize(&mut self, total: f32) {
+        let scale = total / self.total();
+        match self {
+            Weights::Separable { column, .. } => {
+                for (_, _, p) in column {
+                    *p *= scale;
+                }
+            }
+            Weights::Full(weights) => {
+                for (_, _, p) in weights {
+                    *p *= scale;
+                }
+            }
+        }
+    }
+}
+
+impl Kernel {
+    /// The kernel of a cluster on a `map_size` x `map_size` map, leaving out voters further than
+    /// `num_sigma` standard deviations along either axis, or further for heavy tailed shapes, as
+    /// far as `MAX_SPREAD` allows. The weights of the kernel add up to roughly
+    /// `weight * map_size^2`, and exactly for heavy tailed shapes, so that the voters left out
+    /// of their tails don't shrink them next to other clusters.
+    pub fn new(map_size: i32, cluster: &Cluster, weight: f32, num_sigma: f32) -> Kernel {
+        let offset = (
+            (cluster.offset.x * map_size as f32).round() as i32,
+            (cluster.offset.y * map_size as f32).round() as i32,
+        );
+        let Covariance {
+            sigma_x, sigma_y, ..
+        } = cluster.covariance;
+        // Validation leaves room for `num_sigma`, tails that reach further stop at `MAX_SPREAD`.
+        let room = MAX_SPREAD - cluster.offset.x.abs().max(cluster.offset.y.abs());
+        let bound = cluster
+            .shape
+            .reach(num_sigma)
+            .min((room / sigma_x.max(sigma_y)).max(num_sigma));
+        let (mut weights, reach_x, reach_y) = match cluster.shape {
+            Shape::Gaussian => Kernel::gaussian(map_size, cluster, weight, bound),
+            Shape::Disc => Kernel::full(map_size, cluster, weight, bound),
+            _ if !cluster.covariance.is_axis_aligned() => {
+                Kernel::full(map_size, cluster, weight, bound)
+            }
+            _ => Kernel::separable(map_size, cluster, weight, bound),
+        };
+        if let Shape::Laplace | Shape::Cauchy = cluster.shape {
+            weights.normalize(weight * (map_size as f32).powi(2));
+        }
+        Kernel {
+            weights,
+            offset,
+            range: (offset.0.abs() + reach_x).max(offset.1.abs() + reach_y),
+        }
+    }
+
+    /// Every voter around a point, as their offset from the point in pixels and their weight.
+    pub fn voters(&self) -> Vec<(i32, i32, f32)> {
+        let (offset_x, offset_y) = self.offset;
+        match &self.weights {
+            Weights::Separable { line, column, .. } => column
+                .iter()
+                .flat_map(|&(dy, shift, p)| {
+                    line.iter()
+                        .map(move |&(dx, q)| (offset_x + dx - shift, offset_y - dy, p * q))
+                })
+                .collect(),
+            Weights::Full(weights) => weights
+                .iter()
+                .map(|&(dx, dy, p)| (offset_x + dx, offset_y + dy, p))
+                .collect(),
+        }
+    }
+
+    /// The sum of the weights of all the voters around a point.
+    pub fn total_weight(&self) -> f32 {
+        self.weights.total()
+    }
+
+    /// Sheared separable weights of a Gaussian cluster, and how far they reach along x and y.
+    fn gaussian(
+        map_size: i32,
+        cluster: &Cluster,
+        weight: f32,
+        num_sigma: f32,
+    ) -> (Weights, i32, i32) {
+        let Covariance {
+            sigma_x,
+            sigma_y,
+            correlation,
+        } = cluster.covariance;
+        let to_pixels = |sigma: f32| ((map_size as f32 * sigma * num_sigma) as i32).max(1);
+
+        // Spread along x once y is known, and how much the mean x moves per pixel along y.
+        let line_sigma = sigma_x * (1.0 - correlation.powi(2)).sqrt();
+        let slope = correlation * sigma_x / sigma_y;
+
+        let line_range = to_pixels(line_sigma);
+        let line = (-line_range..line_range)
+            .map(|dx| {
+                (
+                    dx,
+                    normal_pdf(0f32, line_sigma, dx as f32 / map_size as f32),
+                )
+            })
+            .collect();
+
+        let column_range = to_pixels(sigma_y);
+        let column: Vec<(i32, i32, f32)> = (-column_range..column_range)
+            .map(|dy| {
+                let shift = (slope * dy as f32).round() as i32;
+                let p = normal_pdf(0f32, sigma_y, dy as f32 / map_size as f32);
+                (dy, shift, p * weight)
+            })
+            .collect();
+
+        let shear = column.iter().map(|&(_, dx, _)| dx.abs()).max().unwrap_or(0);
+        let weights = Weights::Separable {
+            line,
+            column,
+            shear,
+        };
+        (weights, line_range + shear, column_range)
+    }
+
+    /// Separable weights of an axis-aligned cluster within `bound` standard deviations of its
+    /// center, and how far they reach along x and y.
+    fn separable(map_size: i32, cluster: &Cluster, weight: f32, bound: f32) -> (Weights, i32, i32) {
+        let shape = cluster.shape;
+        let samples = |sigma: f32| {
+            let range = ((map_size as f32 * sigma * bound) as i32).max(1);
+            let samples: Vec<(i32, f32)> = (-range..=range)
+                .map(|d| {
+                    let u = d as f32 / map_size as f32 / sigma;
+                    (d, shape.density(u) / sigma)
+                })
+                .filter(|&(_, p)| p > 0.0)
+                .collect();
+            (samples, range)
+        };
+        let (line, reach_x) = samples(cluster.covariance.sigma_x);
+        let (column, reach_y) = samples(cluster.covariance.sigma_y);
+        let weights = Weights::Separable {
+            line,
+            column: column
+                .into_iter()
+                .map(|(dy, p)| (dy, 0, p * weight))
+                .collect(),
+            shear: 0,
+        };
+        (weights, reach_x, reach_y)
+    }
+
+    /// Weights of every voter within `bound` standard deviations of the center, and how far they
+    /// reach along x and y.
+    ///
+    /// Offsets are mapped to standard deviations from the center with the inverse of the
+    /// Cholesky factor of the covariance, which undoes both the scaling and the correlation.
+    fn full(map_size: i32, cluster: &Cluster, weight: f32, bound: f32) -> (Weights, i32, i32) {
+        let shape = cluster.shape;
+        let Covariance {
+            sigma_x,
+            sigma_y,
+            correlation,
+        } = cluster.covariance;
+        // The covariance is L * L^T, with L = [[l11, 0], [l21, l22]].
+        let (l11, l21, l22) = (
+            sigma_x,
+            correlation * sigma_y,
+            sigma_y * (1.0 - correlation.powi(2)).sqrt(),
+        );
+
+        // Points within `bound` standard deviations along both axes lie within this box.
+        let to_pixels =
+            |sigma: f32| ((map_size as f32 * sigma * bound * 2f32.sqrt()) as i32).max(1);
+        let (reach_x, reach_y) = (to_pixels(sigma_x), to_pixels(sigma_y));
+        let mut weights = vec![];
+        for dx in -reach_x..=reach_x {
+            for dy in -reach_y..=reach_y {
+                let x = dx as f32 / map_size as f32;
+                let y = dy as f32 / map_size as f32;
+                let u = x / l11;
+                let v = (y - l21 * u) / l22;
+                let within = match shape {
+                    Shape::Disc => u * u + v * v <= bound * bound,
+                    _ => u.abs() <= bound && v.abs() <= bound,
+                };
+                let p = shape.density_2d(u, v) / (l11 * l22);
+                if within && p > 0.0 {
+                    weights.push((dx, dy, p * weight));
+                }
+            }
+        }
+        (Weights::Full(weights), reach_x, reach_y)
+    }
+}
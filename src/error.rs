@@ -0,0 +1,310 @@
+//! Errors reported by the simulator.
+
+use std::fmt;
+
+use crate::{BallotType, Strategy, Truncation};
+
+/// Reasons why an election can't be simulated.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElectionError {
+    /// No election method with this id is registered.
+    UnknownMethod(String),
+    /// The election method has no parameter with this id.
+    UnknownParameter {
+        method: &'static str,
+        parameter: String,
+        value: f32,
+    },
+    /// The election method takes `expected` parameter values, but `count` were given.
+    ParameterCount {
+        method: &'static str,
+        count: usize,
+        expected: usize,
+    },
+    /// The value is out of range for the parameter with this id.
+    InvalidParameter {
+        id: &'static str,
+        value: f32,
+    },
+    /// The registry has only `count` methods, so none has this index.
+    MethodIndexOutOfRange {
+        index: usize,
+        count: usize,
+    },
+    /// Candidate coordinates must come in (x, y) pairs.
+    OddCoordinates(usize),
+    /// A coordinate of the candidate with this index isn't finite.
+    NonFiniteCoordinate(usize),
+    NoCandidates,
+    TooManyCandidates {
+        count: usize,
+        max: usize,
+    },
+    /// Either every candidate or none has a valence.
+    ValenceMismatch {
+        count: usize,
+        num_candidates: usize,
+    },
+    /// The candidate with this index has a bonus that isn't finite, or a quality that isn't
+    /// positive and finite.
+    InvalidValence(usize),
+    /// The temperature of a random voter model must be positive and finite.
+    InvalidTemperature(f32),
+    /// A random voter model must draw at least one ballot, and at most `max`, at each point.
+    InvalidDraws {
+        draws: usize,
+        max: usize,
+    },
+    /// The strategy doesn't apply to the ballots of the election method.
+    StrategyMismatch {
+        strategy: Strategy,
+        ballot_type: BallotType,
+    },
+    /// The share of strategic voters must be between 0 and 1.
+    InvalidStrategicShare(f32),
+    /// Voters must rank at least their favorite candidate, or all candidates within a positive
+    /// radius.
+    InvalidTruncation(Truncation),
+    /// The distance over which alienation halves turnout must be positive.
+    InvalidAlienation(f32),
+    /// The difference in distance at which indifference halves turnout must be positive.
+    InvalidIndifference(f32),
+    /// The map must be at least one pixel wide.
+    ZeroSize,
+    /// The cluster of the electorate with this index has a negative weight, or a weight or
+    /// offset that isn't finite.
+    InvalidCluster(usize),
+    /// The weights of the clusters of the electorate must add up to more than 0.
+    EmptyElectorate,
+    /// The standard deviations of the electorate must be positive and finite.
+    InvalidSigma(f32),
+    /// The correlation of the electorate must be strictly between -1 and 1.
+    InvalidCorrelation(f32),
+    /// The number of standard deviations to simulate must be positive and finite.
+    InvalidNumSigma(f32),
+    /// The order of a Minkowski metric must be at least 1 and finite.
+    InvalidMinkowskiOrder(f32),
+    /// The salience of both axes must be positive and finite.
+    InvalidSalience {
+        x: f32,
+        y: f32,
+    },
+    /// The axes of a slice must be two different issues of the issue space.
+    InvalidAxes {
+        x: usize,
+        y: usize,
+        dimensions: usize,
+    },
+    /// The candidate with this index doesn't have a coordinate for every issue of the slice.
+    DimensionMismatch {
+        candidate: usize,
+        count: usize,
+        dimensions: usize,
+    },
+    /// The hidden issue with this index has a center that isn't finite, or a standard deviation
+    /// that isn't positive.
+    InvalidHiddenIssue(usize),
+    /// Voters must be sampled at least once along each hidden issue.
+    ZeroSamples,
+    /// A sampled electorate needs at least one voter.
+    ZeroVoters,
+    /// At least one election must be sampled.
+    ZeroRepeats,
+    /// Ballots can only be integrated exactly for sincere voters, who all turn out and rank
+    /// candidates by Euclidean distance, in Gaussian clusters. Names what the election has
+    /// instead.
+    ExactUnsupported(&'static str),
+    /// Exact integration takes too long for more than `MAX_EXACT_CANDIDATES` candidates.
+    TooManyExactCandidates {
+        count: usize,
+        max: usize,
+    },
+    TooManyHiddenPositions {
+        count: usize,
+        max: usize,
+    },
+    /// Some cluster of the electorate reaches further than `MAX_SPREAD`, counting its offset and
+    /// how far its kernel reaches to keep `num_sigma` standard deviations.
+    SpreadTooLarge {
+        spread: f32,
+        max: f32,
+    },
+}
+
+impl fmt::Display for ElectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElectionError::UnknownMethod(id) => write!(f, "unknown election method \"{}\"", id),
+            ElectionError::OddCoordinates(len) => write!(
+                f,
+                "candidate coordinates must be (x, y) pairs, got {} values",
+                len
+            ),
+            ElectionError::UnknownParameter {
+                method,
+                parameter,
+                value,
+            } => write!(
+                f,
+                "{} has no parameter \"{}\" to set to {}",
+                method, parameter, value
+            ),
+            ElectionError::ParameterCount {
+                method,
+                count,
+                expected,
+            } => write!(f, "{} takes {} parameters, got {}", method, expected, count),
+            ElectionError::InvalidParameter { id, value } => {
+                write!(f, "invalid value {} for parameter {}", value, id)
+            }
+            ElectionError::MethodIndexOutOfRange { index, count } => write!(
+                f,
+                "no election method at index {}, there are {}",
+                index, count
+            ),
+            ElectionError::NonFiniteCoordinate(i) => {
+                write!(f, "candidate {} has a coordinate that isn't finite", i)
+            }
+            ElectionError::NoCandidates => write!(f, "at least one candidate is required"),
+            ElectionError::TooManyCandidates { count, max } => write!(
+                f,
+                "{} candidates given, but at most {} are supported",
+                count, max
+            ),
+            ElectionError::ValenceMismatch {
+                count,
+                num_candidates,
+            } => write!(
+                f,
+                "{} valences given for {} candidates",
+                count, num_candidates
+            ),
+            ElectionError::InvalidValence(i) => write!(
+                f,
+                "candidate {} must have a finite bonus and a positive, finite quality",
+                i
+            ),
+            ElectionError::InvalidTemperature(temperature) => write!(
+                f,
+                "temperature must be positive and finite, got {}",
+                temperature
+            ),
+            ElectionError::InvalidDraws { draws, max } => write!(
+                f,
+                "voters must draw between 1 and {} ballots, got {}",
+                max, draws
+            ),
+            ElectionError::StrategyMismatch {
+                strategy,
+                ballot_type,
+            } => write!(
+                f,
+                "{:?} voting doesn't apply to {:?} ballots",
+                strategy, ballot_type
+            ),
+            ElectionError::InvalidStrategicShare(share) => write!(
+                f,
+                "the share of strategic voters must be between 0 and 1, got {}",
+                share
+            ),
+            ElectionError::InvalidTruncation(truncation) => match truncation {
+                Truncation::Radius(radius) => write!(
+                    f,
+                    "voters must rank candidates within a positive radius, got {}",
+                    radius
+                ),
+                _ => write!(f, "voters must rank at least one candidate"),
+            },
+            ElectionError::InvalidAlienation(alienation) => write!(
+                f,
+                "alienation must be a positive distance, got {}",
+                alienation
+            ),
+            ElectionError::InvalidIndifference(indifference) => write!(
+                f,
+                "indifference must be a positive distance, got {}",
+                indifference
+            ),
+            ElectionError::ZeroSize => write!(f, "map size must be at least 1"),
+            ElectionError::InvalidCluster(i) => write!(
+                f,
+                concat!(
+                    "cluster {} of the electorate needs a finite, non-negative weight ",
+                    "and a finite offset"
+                ),
+                i
+            ),
+            ElectionError::EmptyElectorate => {
+                write!(f, "the electorate needs a cluster with a positive weight")
+            }
+            ElectionError::InvalidSigma(sigma) => {
+                write!(f, "sigma must be positive and finite, got {}", sigma)
+            }
+            ElectionError::InvalidCorrelation(correlation) => write!(
+                f,
+                "correlation must be between -1 and 1, got {}",
+                correlation
+            ),
+            ElectionError::InvalidNumSigma(num_sigma) => write!(
+                f,
+                "num_sigma must be positive and finite, got {}",
+                num_sigma
+            ),
+            ElectionError::InvalidMinkowskiOrder(p) => write!(
+                f,
+                "the order of a Minkowski metric must be at least 1 and finite, got {}",
+                p
+            ),
+            ElectionError::InvalidSalience { x, y } => write!(
+                f,
+                "salience must be positive and finite, got {} and {}",
+                x, y
+            ),
+            ElectionError::InvalidAxes { x, y, dimensions } => write!(
+                f,
+                "the axes of the map must be two different issues out of {}, got {} and {}",
+                dimensions, x, y
+            ),
+            ElectionError::DimensionMismatch {
+                candidate,
+                count,
+                dimensions,
+            } => write!(
+                f,
+                "candidate {} has {} coordinates, expected {}",
+                candidate, count, dimensions
+            ),
+            ElectionError::InvalidHiddenIssue(i) => write!(
+                f,
+                "hidden issue {} must have a finite center and a positive standard deviation",
+                i
+            ),
+            ElectionError::ZeroSamples => write!(f, "voters must be sampled along hidden issues"),
+            ElectionError::ZeroVoters => write!(f, "at least one voter must be sampled"),
+            ElectionError::ZeroRepeats => write!(f, "at least one election must be sampled"),
+            ElectionError::ExactUnsupported(feature) => {
+                write!(f, "exact integration doesn't support {}", feature)
+            }
+            ElectionError::TooManyExactCandidates { count, max } => write!(
+                f,
+                "{} candidates given, but exact integration supports at most {}",
+                count, max
+            ),
+            ElectionError::TooManyHiddenPositions { count, max } => write!(
+                f,
+                concat!(
+                    "voters would be sampled at {} positions along the hidden issues, ",
+                    "at most {} are supported"
+                ),
+                count, max
+            ),
+            ElectionError::SpreadTooLarge { spread, max } => write!(
+                f,
+                "electorates may spread at most {} around their center, got {}",
+                max, spread
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ElectionError {}
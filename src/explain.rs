@@ -0,0 +1,69 @@
+//! Step by step account of how the election at a single point was decided.
+
+use serde::Serialize;
+
+use crate::Outcome;
+
+/// How the election of an electorate centered on a single point was decided.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Explanation {
+    pub outcome: Outcome,
+    /// Every round of the election, in order. Methods with a single round have exactly one.
+    pub rounds: Vec<Round>,
+    /// For pairwise methods, `pairwise[a][b]` is the share of the voters with a preference between
+    /// `a` and `b` who prefer `a`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pairwise: Option<Vec<Vec<f32>>>,
+}
+
+/// A single round of an election.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Round {
+    /// Vote share of each candidate, relative to the total votes of the first round.
+    pub shares: Vec<f32>,
+    /// The candidate eliminated at the end of this round, if any.
+    pub eliminated: Option<usize>,
+    /// How the vote share of each candidate changes in the next round; empty for the last round.
+    pub transfers: Vec<f32>,
+}
+
+impl Explanation {
+    /// Explains an election from the vote totals of each of its rounds.
+    ///
+    /// `eliminated` lists the candidate eliminated in each round but the last, if known.
+    pub fn from_rounds(
+        outcome: Outcome,
+        totals: &[&[f32]],
+        eliminated: &[Option<usize>],
+    ) -> Explanation {
+        let total: f32 = totals[0].iter().sum();
+        let shares: Vec<Vec<f32>> = totals
+            .iter()
+            .map(|votes| {
+                votes
+                    .iter()
+                    .map(|&v| if total > 0.0 { v / total } else { v })
+                    .collect()
+            })
+            .collect();
+        let rounds = (0..shares.len())
+            .map(|round| Round {
+                shares: shares[round].clone(),
+                eliminated: eliminated.get(round).cloned().flatten(),
+                transfers: match shares.get(round + 1) {
+                    Some(next) => next
+                        .iter()
+                        .zip(&shares[round])
+                        .map(|(next, share)| next - share)
+                        .collect(),
+                    None => vec![],
+                },
+            })
+            .collect();
+        Explanation {
+            outcome,
+            rounds,
+            pairwise: None,
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Fast Fourier transforms, to convolve large maps with their electorate.
+
+use std::f64::consts::TAU;
+use std::ops::{Add, Mul};
+
+/// How the ballots on the grid are convolved with the electorate around each point.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Convolution {
+    /// Fourier transforms for maps from `FFT_MIN_SIZE` on, unless they would cost more than
+    /// direct sums, as they do for many distinct ballots.
+    #[default]
+    Auto,
+    /// Sums up the voters around each point, which costs `range` operations per voter and
+    /// point, along each axis.
+    Direct,
+    /// Multiplies the Fourier transforms of each row and column of voters with those of the
+    /// electorate, which only costs `log(range)` operations per voter, for each candidate.
+    ///
+    /// Clusters that aren't Gaussian, or whose shape is rotated, are still summed up directly.
+    Fft,
+}
+
+/// Maps from this size on are convolved with Fourier transforms by `Convolution::Auto`.
+pub const FFT_MIN_SIZE: i32 = 256;
+
+/// Operations of direct sums that take as long as a butterfly of a transform.
+const BUTTERFLY_COST: usize = 2;
+
+/// Most complex values transformed at once, in batches of line sums.
+pub(crate) const MAX_BATCH: usize = 1 << 22;
+
+/// Totals smaller than this fraction of the largest possible one are rounding errors of the
+/// transforms, which would otherwise turn candidates without a single vote into near ties.
+pub(crate) const FLUSH: f64 = 1e-10;
+
+/// Laid out as its real part followed by its imaginary part, which vector kernels rely on.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[repr(C)]
+pub(crate) struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+/// Radix 2 transforms of `len` values, a power of two.
+pub(crate) struct Fft {
+    len: usize,
+    /// `exp(-2πik / len)` for the first half of `k`.
+    twiddles: Vec<Complex>,
+}
+
+impl Fft {
+    /// Transforms of at least `len` values.
+    pub fn new(len: usize) -> Fft {
+        let len = len.next_power_of_two();
+        let twiddles = (0..len / 2)
+            .map(|k| {
+                let angle = -TAU * k as f64 / len as f64;
+                Complex::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        Fft { len, twiddles }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Replaces `data` with its discrete Fourier transform.
+    pub fn forward(&self, data: &mut [Complex]) {
+        self.transform(data, false);
+    }
+
+    /// Replaces the transform in `data` with the values it was taken of.
+    pub fn inverse(&self, data: &mut [Complex]) {
+        self.transform(data, true);
+        let scale = 1.0 / self.len as f64;
+        for value in data.iter_mut() {
+            value.re *= scale;
+            value.im *= scale;
+        }
+    }
+
+    fn transform(&self, data: &mut [Complex], inverse: bool) {
+        let len = self.len;
+        assert_eq!(data.len(), len);
+        let bits = len.trailing_zeros();
+        if bits == 0 {
+            return;
+        }
+        for i in 0..len {
+            let j = i.reverse_bits() >> (usize::BITS - bits);
+            if i < j {
+                data.swap(i, j);
+            }
+        }
+        let mut half = 1;
+        while half < len {
+            let stride = len / (2 * half);
+            for start in (0..len).step_by(2 * half) {
+                for k in 0..half {
+                    let mut twiddle = self.twiddles[k * stride];
+                    if inverse {
+                        twiddle.im = -twiddle.im;
+                    }
+                    let odd = data[start + k + half] * twiddle;
+                    let even = data[start + k];
+                    data[start + k] = even + odd;
+                    data[start + k + half] = Complex::new(even.re - odd.re, even.im - odd.im);
+                }
+            }
+            half *= 2;
+        }
+    }
+}
+
+/// Whether transforms are likely faster than direct sums, to add up `width` totals per point
+/// over a `lines` x `rows` grid of line sums, each of `line_len` voters, with `column_len` line
+/// sums per point.
+///
+/// Direct sums add up every voter along a line once, then scatter the few totals a line holds,
+/// about one per candidate, to every point along the column. Transforms of rows and columns of
+/// the grid are taken for every pair of totals.
+pub(crate) fn pays_off(
+    width: usize,
+    num_candidates: usize,
+    lines: usize,
+    rows: usize,
+    line_len: usize,
+    column_len: usize,
+) -> bool {
+    let direct = lines * rows * (line_len + column_len * width.min(num_candidates));
+    let len = rows.next_power_of_two();
+    let log_len = len.trailing_zeros() as usize;
+    let transforms = (rows + 2 * lines) * len * log_len;
+    width.div_ceil(2) * transforms * BUTTERFLY_COST < direct
+}
+
+/// The transform of `(d, weight)` taps, to compute `Σ weight * signal[i + d]` at every `i` of a
+/// signal by multiplying transforms.
+///
+/// Indices `i + d` wrap around the length of the transform, which must leave enough room after
+/// the signal for them to land in zeros.
+pub(crate) fn correlation(fft: &Fft, taps: impl IntoIterator<Item = (i32, f32)>) -> Vec<Complex> {
+    let len = fft.len() as i32;
+    let mut spectrum = vec![Complex::default(); fft.len()];
+    for (d, weight) in taps {
+        spectrum[(-d).rem_euclid(len) as usize].re += weight as f64;
+    }
+    fft.forward(&mut spectrum);
+    spectrum
+}
+
+/// Length of the transforms of a signal of `len` values, reading indices `first..=last`, which
+/// may lie outside of the signal, without the wrapped indices reading any of it.
+pub(crate) fn padded_len(len: usize, first: i32, last: i32) -> usize {
+    let end = (len as i32).max(last + 1);
+    (end - first.min(0)) as usize
+}
@@ -0,0 +1,111 @@
+//! The weight of each distinct ballot cast around each point of the map.
+
+use std::collections::HashMap;
+
+use crate::ballot::Ballot;
+use crate::parallel;
+
+/// Grids are only tallied by ballot type while this many weights, one per point and ballot type,
+/// fit in memory. Beyond that, ballots are summed up directly for each count.
+pub(crate) const MAX_WEIGHTS: usize = 1 << 24;
+
+/// Numbers the distinct ballots in the order they are first seen.
+pub(crate) struct BallotTypes {
+    num_candidates: usize,
+    /// Every distinct ballot, `num_candidates` bytes each.
+    ballots: Vec<u8>,
+    index: HashMap<Vec<u8>, u32>,
+}
+
+impl BallotTypes {
+    pub fn new(num_candidates: usize) -> BallotTypes {
+        BallotTypes {
+            num_candidates,
+            ballots: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    /// The number of distinct ballots seen so far.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// The number of `ballot`, which is new if no such ballot was seen before.
+    pub fn number(&mut self, ballot: &[u8]) -> u32 {
+        if let Some(&number) = self.index.get(ballot) {
+            return number;
+        }
+        let number = self.index.len() as u32;
+        self.index.insert(ballot.to_vec(), number);
+        self.ballots.extend_from_slice(ballot);
+        number
+    }
+}
+
+/// `(ballot type, weight)` of the ballots cast around each point, so that each count only visits
+/// the few distinct ballots of a point rather than every voter around it.
+pub(crate) struct Histogram {
+    num_candidates: usize,
+    ballots: Vec<u8>,
+    /// The counts of every point, one point after the other.
+    counts: Vec<(u32, f32)>,
+    /// Where the counts of each point start in `counts`, followed by the end of the last one.
+    starts: Vec<usize>,
+}
+
+impl Histogram {
+    pub fn new(types: BallotTypes) -> Histogram {
+        Histogram {
+            num_candidates: types.num_candidates,
+            ballots: types.ballots,
+            counts: vec![],
+            starts: vec![0],
+        }
+    }
+
+    /// Adds the next point, with the weight of each ballot type around it.
+    pub fn push(&mut self, counts: impl IntoIterator<Item = (u32, f32)>) {
+        self.counts
+            .extend(counts.into_iter().filter(|&(_, weight)| weight > 0.0));
+        self.starts.push(self.counts.len());
+    }
+
+    /// The raw bytes of every ballot type, in the order they are numbered.
+    pub fn ballots(&self) -> &[u8] {
+        &self.ballots
+    }
+
+    /// The raw bytes of a ballot type.
+    fn ballot(&self, ballot: u32) -> &[u8] {
+        let i = ballot as usize * self.num_candidates;
+        &self.ballots[i..i + self.num_candidates]
+    }
+
+    /// Adds up the ballots around `point` into `votes`, as `Ballots::sum_votes` does for every
+    /// point of the map.
+    pub fn sum_votes_at<'a, B, F>(&'a self, point: usize, votes: &mut [f32], count_votes: &F)
+    where
+        B: Ballot<'a>,
+        F: Fn(&mut [f32], B, f32),
+    {
+        let counts = &self.counts[self.starts[point]..self.starts[point + 1]];
+        for &(ballot, p) in counts {
+            count_votes(votes, B::from_raw(self.ballot(ballot)), p);
+        }
+    }
+
+    /// Adds up the ballots around every point.
+    pub fn sum_votes<'a, B, F>(&'a self, count_votes: &F) -> Vec<f32>
+    where
+        B: Ballot<'a>,
+        F: Fn(&mut [f32], B, f32) + Sync,
+    {
+        let num_points = self.starts.len() - 1;
+        let mut num_votes = vec![0f32; num_points * self.num_candidates];
+        parallel::for_each_chunk(&mut num_votes, self.num_candidates, |point, votes| {
+            self.sum_votes_at(point, votes, count_votes)
+        });
+        num_votes
+    }
+}
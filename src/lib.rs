@@ -1,8 +1,59 @@
+pub mod ballot;
+mod color;
+mod electorate;
+mod error;
+mod explain;
+mod fft;
+mod histogram;
+mod method;
+mod metric;
+mod noise;
+mod parallel;
+mod region;
+mod result;
+mod sample;
+mod simd;
+mod space;
+mod strategy;
+mod tie;
+mod truncation;
+mod turnout;
 mod utils;
 
+use std::str::FromStr;
+use std::sync::OnceLock;
+
 use wasm_bindgen::prelude::*;
 
+pub use ballot::BallotType;
+use ballot::{Approval as ApprovalBallot, Ballot, BallotMut, Ranking, Single};
+pub use color::{palette, Color, CANDIDATE_COLORS};
+pub use electorate::{Cluster, Covariance, Electorate, Shape};
+use electorate::{Kernel, Weights};
+pub use error::ElectionError;
+pub use explain::{Explanation, Round};
+use fft::{correlation, padded_len, pays_off, Complex, Fft, FLUSH, MAX_BATCH};
+pub use fft::{Convolution, FFT_MIN_SIZE};
+use histogram::{BallotTypes, Histogram, MAX_WEIGHTS};
+pub use method::{
+    Approval, Borda, ElectionMethod, Hare, MethodRegistry, Minimax, Parameter, Plurality,
+};
+pub use metric::{Metric, Valence, Voter};
+pub use noise::{VoterModel, NOISE_LATTICE};
+pub use region::Integration;
+pub use result::{ElectionResult, Winners};
+pub use sample::{sample_election, SampledResult, Sampling};
+use space::Projection;
+pub use space::{HiddenIssue, Slice};
+use strategy::Poll;
+pub use strategy::Strategy;
+use tie::TieContext;
+pub use tie::{Goal, Outcome, TieBreak, TIE_TOLERANCE};
+pub use truncation::Truncation;
+pub use turnout::Turnout;
+
 // A macro to provide `println!(..)`-style syntax for `console.log` logging.
+#[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
         #[cfg(feature = "debug_logging")]
@@ -10,28 +61,6 @@ macro_rules! log {
     };
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
-pub struct Color {
-    pub r: u8,
-    pub g: u8,
-    pub b: u8,
-}
-
-impl Color {
-    pub const RED: Color = Color { r: 255, g: 0, b: 0 };
-    pub const GREEN: Color = Color { r: 0, g: 255, b: 0 };
-    pub const BLUE: Color = Color { r: 0, g: 0, b: 255 };
-    pub const YELLOW: Color = Color {
-        r: 255,
-        g: 255,
-        b: 0,
-    };
-    pub const PINK: Color = Color {
-        r: 255,
-        g: 20,
-        b: 147,
-    };
-}
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
 pub struct Point {
     pub x: f32,
@@ -43,7 +72,7 @@ impl Point {
         Point { x, y }
     }
 
-    fn l2_square(&self, other: &Point) -> f32 {
+    pub(crate) fn l2_square(&self, other: &Point) -> f32 {
         (self.x - other.x).powi(2) + (self.y - other.y).powi(2)
     }
 }
@@ -74,359 +103,1699 @@ impl Image {
     }
 }
 
-fn normal_pdf(mean: f32, sigma: f32, x: f32) -> f32 {
-    1f32 / (sigma * (2f32 * std::f32::consts::PI).sqrt())
-        * (-1f32 / 2f32 * ((x - mean) / sigma).powi(2)).exp()
+/// The largest number of candidates an election can have.
+pub const MAX_CANDIDATES: usize = 64;
+
+/// The electorate may reach at most this far from its center, in map units.
+///
+/// Ballots are cast on a grid padded by this distance on each side of the map, so this bounds the
+/// memory and time an election takes.
+pub const MAX_SPREAD: f32 = 4.0;
+
+/// The largest number of ballots a random `VoterModel` may draw at each point.
+pub const MAX_DRAWS: usize = 256;
+
+/// The largest number of positions along the hidden issues of a `Slice` that voters may be
+/// sampled at. Ballots are cast over the whole map at each of them.
+pub const MAX_HIDDEN_POSITIONS: usize = 256;
+
+/// The largest number of candidates `Integration::Exact` supports. The number of regions it
+/// integrates over grows with the fourth power of the number of candidates.
+pub const MAX_EXACT_CANDIDATES: usize = 8;
+
+/// Options that control how an election is simulated.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub tie_break: TieBreak,
+    /// How voters measure their distance to candidates.
+    pub metric: Metric,
+    /// The valence of each candidate, or empty if no candidate has one.
+    pub valence: Vec<Valence>,
+    /// How voters turn their distances to candidates into ballots.
+    pub voter_model: VoterModel,
+    /// Which candidates voters rank on ranked ballots.
+    pub truncation: Truncation,
+    /// How many voters turn out at each location.
+    pub turnout: Turnout,
+    /// How voters adapt their ballots to a poll of the sincere election.
+    pub strategy: Strategy,
+    /// Share of the voters who follow `strategy`, the others vote sincerely.
+    pub strategic_share: f32,
+    /// The voters around each point.
+    pub electorate: Electorate,
+    /// Voters further than this many standard deviations from the center of their cluster,
+    /// along either axis, are left out. Laplace and Cauchy clusters reach two and four times as
+    /// far, up to `MAX_SPREAD`, and are scaled back up to their whole weight. Clusters of other
+    /// shapes just lose the voters beyond the cutoff.
+    pub num_sigma: f32,
+    /// How the ballots of the electorate are added up.
+    pub integration: Integration,
+    /// How the ballots on the grid are convolved with the electorate.
+    pub convolution: Convolution,
 }
 
-fn vote_plurality(p: Point, candidates: &[Point], votes: &mut [u8]) {
-    let mut closest_i = 100000000;
-    let mut closest_dist = std::f32::MAX;
-    for (i, c) in candidates.iter().enumerate() {
-        let dist = p.l2_square(c);
-        if dist < closest_dist {
-            closest_dist = dist;
-            closest_i = i;
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            tie_break: TieBreak::default(),
+            metric: Metric::default(),
+            valence: vec![],
+            voter_model: VoterModel::default(),
+            truncation: Truncation::default(),
+            turnout: Turnout::default(),
+            strategy: Strategy::default(),
+            strategic_share: 1.0,
+            electorate: Electorate::gaussian(Covariance::isotropic(0.5 / 1.5)),
+            num_sigma: 3.0,
+            integration: Integration::default(),
+            convolution: Convolution::default(),
         }
     }
-    votes[closest_i] = 1;
 }
 
-fn vote_close(p: Point, candidates: &[Point], votes: &mut [u8]) {
-    for (i, c) in candidates.iter().enumerate() {
-        let dist = p.l2_square(c);
-        if dist < 1.0 {
-            votes[i] = 1;
+/// How to color the points of the map.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Shading {
+    /// Every point has the color of its winner.
+    #[default]
+    Flat,
+    /// The winner's color fades towards white as the margin of victory shrinks.
+    Margin,
+    /// The winner's color fades towards white as more ballots are exhausted.
+    Exhausted,
+    /// The winner's color fades towards white as fewer voters turn out.
+    Turnout,
+}
+
+impl FromStr for Shading {
+    type Err = String;
+
+    /// Parses `flat`, `margin`, `exhausted` or `turnout`.
+    fn from_str(s: &str) -> Result<Shading, String> {
+        match s {
+            "flat" => Ok(Shading::Flat),
+            "margin" => Ok(Shading::Margin),
+            "exhausted" => Ok(Shading::Exhausted),
+            "turnout" => Ok(Shading::Turnout),
+            _ => Err(s.to_string()),
         }
     }
 }
 
-fn vote_rank(p: Point, candidates: &[Point], votes: &mut [u8]) {
-    let mut min_dist = std::f32::MIN;
-    let mut prev_i = 1000000;
-    for rank in 0..candidates.len() {
-        let mut closest_i = 100000000;
-        let mut closest_dist = std::f32::MAX;
-        for (i, c) in candidates.iter().enumerate() {
-            let dist = p.l2_square(c);
-            if dist < closest_dist && dist >= min_dist && i != prev_i {
-                closest_dist = dist;
-                closest_i = i;
-            }
+/// Margins at or above this are drawn in the winner's full color.
+const FULL_MARGIN: f32 = 0.3;
+
+/// Draws the result as RGBA pixels in row-major order, ready to be put on a canvas.
+///
+/// If `highlight_ties` is set, points where a tie had to be broken are drawn in `Color::TIE`
+/// instead of the winner's color.
+pub fn draw(result: &ElectionResult, shading: Shading, highlight_ties: bool) -> Vec<u8> {
+    let size = result.size;
+    let colors = palette(result.num_candidates);
+    // How strongly to draw the winner's color at each point, if shaded.
+    let strengths: Vec<f32> = match shading {
+        Shading::Flat => vec![],
+        Shading::Margin => result
+            .margins()
+            .iter()
+            .map(|margin| (margin / FULL_MARGIN).min(1.0).sqrt())
+            .collect(),
+        Shading::Exhausted => result.exhausted.iter().map(|e| 1.0 - e).collect(),
+        Shading::Turnout => result.turnout.clone(),
+    };
+    let mut image = Image::new(size);
+    for x in 0..size {
+        for y in 0..size {
+            let outcome = result.outcome_at(x, y);
+            let color = if outcome.tied && highlight_ties {
+                Color::TIE
+            } else if let Some(strength) = strengths.get(x * size + y) {
+                // Keep a hint of color even for exact ties, so the winner can still be told apart.
+                Color::WHITE.mix(colors[outcome.winner], 0.15 + 0.85 * strength)
+            } else {
+                colors[outcome.winner]
+            };
+            image.set_coords(x, y, color);
         }
-        min_dist = closest_dist;
-        prev_i = closest_i;
-        votes[closest_i] = (1 + rank) as u8;
     }
+    image.data
 }
 
-fn max_vote_candidate(votes: &[f32]) -> usize {
-    votes
-        .iter()
-        .enumerate()
-        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-        .map(|(i, _)| i)
-        .unwrap()
+/// Draws the candidate that won most often at each point of a sampled election as RGBA pixels,
+/// fading towards white as the outcome gets less certain.
+pub fn draw_sampled(result: &SampledResult) -> Vec<u8> {
+    let size = result.size;
+    let colors = palette(result.num_candidates);
+    let uncertainty = result.uncertainty();
+    let mut image = Image::new(size);
+    for (point, winner) in result.winners().enumerate() {
+        let certainty = 1.0 - uncertainty[point];
+        let color = Color::WHITE.mix(colors[winner], 0.15 + 0.85 * certainty);
+        image.set_coords(point / size, point % size, color);
+    }
+    image.data
 }
 
-fn min_vote_candidate(votes: &[f32]) -> usize {
-    votes
-        .iter()
-        .enumerate()
-        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
-        .map(|(i, _)| i)
-        .unwrap()
+/// Builds the settings passed from the web page. `tie_break` is parsed by `TieBreak::from_str`,
+/// `metric` by `Metric::from_str`, `voter_model` by `VoterModel::from_str`, `truncation` by
+/// `Truncation::from_str`, `turnout` by `Turnout::from_str`, `shape`, the shape of all
+/// clusters, by `Shape::from_str` and `integration` by `Integration::from_str`. `strategy` is
+/// parsed by `Strategy::from_str`, optionally followed by `:<strategic_share>`.
+///
+/// `electorate` starts with `num_sigma`, followed by the `weight`, `offset.x`, `offset.y`,
+/// `sigma_x`, `sigma_y` and `correlation` of each cluster. `valence` holds the `bonus` and
+/// `quality` of each candidate, or nothing.
+#[allow(clippy::too_many_arguments)]
+fn parse_settings(
+    tie_break: &str,
+    metric: &str,
+    voter_model: &str,
+    truncation: &str,
+    turnout: &str,
+    strategy: &str,
+    shape: &str,
+    integration: &str,
+    electorate: &[f32],
+    valence: &[f32],
+) -> Result<Settings, JsValue> {
+    let (strategy, strategic_share) = match strategy.split_once(':') {
+        Some((name, share)) => match share.parse() {
+            Ok(share) => (name, share),
+            Err(_) => return Err(format!("invalid strategic share in {}", strategy).into()),
+        },
+        None => (strategy, 1.0),
+    };
+    let shape: Shape = shape
+        .parse()
+        .map_err(|shape| format!("unknown electorate shape {}", shape))?;
+    let (num_sigma, clusters) = match electorate.split_first() {
+        Some((&num_sigma, clusters)) if clusters.len() % 6 == 0 => (num_sigma, clusters),
+        _ => {
+            let message = format!(
+                "expected num_sigma and 6 values per cluster, got {} values",
+                electorate.len()
+            );
+            return Err(message.into());
+        }
+    };
+    let clusters = clusters
+        .chunks(6)
+        .map(|c| Cluster {
+            weight: c[0],
+            offset: Point::new(c[1], c[2]),
+            shape,
+            covariance: Covariance {
+                sigma_x: c[3],
+                sigma_y: c[4],
+                correlation: c[5],
+            },
+        })
+        .collect();
+    if !valence.len().is_multiple_of(2) {
+        let message = format!(
+            "expected 2 values per valence, got {} values",
+            valence.len()
+        );
+        return Err(message.into());
+    }
+    let valence = valence
+        .chunks(2)
+        .map(|v| Valence {
+            bonus: v[0],
+            quality: v[1],
+        })
+        .collect();
+    Ok(Settings {
+        tie_break: tie_break
+            .parse()
+            .map_err(|tie_break| format!("unknown tie break {}", tie_break))?,
+        metric: metric
+            .parse()
+            .map_err(|metric| format!("unknown metric {}", metric))?,
+        valence,
+        voter_model: voter_model
+            .parse()
+            .map_err(|model| format!("unknown voter model {}", model))?,
+        truncation: truncation
+            .parse()
+            .map_err(|truncation| format!("unknown truncation {}", truncation))?,
+        turnout: turnout
+            .parse()
+            .map_err(|turnout| format!("unknown turnout {}", turnout))?,
+        strategy: strategy
+            .parse()
+            .map_err(|strategy| format!("unknown strategy {}", strategy))?,
+        strategic_share,
+        electorate: Electorate { clusters },
+        num_sigma,
+        integration: integration
+            .parse()
+            .map_err(|integration| format!("unknown integration {}", integration))?,
+        convolution: Convolution::default(),
+    })
 }
 
-pub const CANDIDATE_COLORS: [Color; 5] = [
-    Color::RED,
-    Color::GREEN,
-    Color::BLUE,
-    Color::YELLOW,
-    Color::PINK,
-];
-
+/// Renders the winner at each point of the map.
+///
+/// `method_parameters` holds a value for each of the `election_method_parameters`, in order.
+/// `shading` is parsed by `Shading::from_str`, see `parse_settings` for the other settings and
+/// `draw` for `highlight_ties`.
+#[allow(clippy::too_many_arguments)]
 #[wasm_bindgen]
 pub fn render(
     size: usize,
     candidate_coords: Vec<f32>,
+    valence: Vec<f32>,
     election_method: &str,
+    method_parameters: Vec<f32>,
+    tie_break: &str,
+    metric: &str,
+    voter_model: &str,
+    truncation: &str,
+    turnout: &str,
+    strategy: &str,
+    shape: &str,
+    integration: &str,
+    electorate: Vec<f32>,
+    shading: &str,
+    highlight_ties: bool,
 ) -> Result<Vec<u8>, JsValue> {
     utils::set_panic_hook();
 
-    let mut candidates = vec![];
-    for i in (0..candidate_coords.len()).step_by(2) {
-        candidates.push(Point::new(candidate_coords[i], candidate_coords[i + 1]));
-    }
-    let winners = election(size as i32, &candidates, election_method);
-    let mut image = Image::new(size);
-    for x in 0..size {
-        for y in 0..size {
-            let winner = winners[x * size + y];
-            image.set_coords(x, y, CANDIDATE_COLORS[winner as usize]);
-        }
+    let mut registry = MethodRegistry::default();
+    let method = registry.configure(election_method, &method_parameters)?;
+    let settings = parse_settings(
+        tie_break,
+        metric,
+        voter_model,
+        truncation,
+        turnout,
+        strategy,
+        shape,
+        integration,
+        &electorate,
+        &valence,
+    )?;
+    let shading = shading
+        .parse()
+        .map_err(|shading| format!("unknown shading {}", shading))?;
+
+    let candidates = parse_candidates(&candidate_coords)?;
+    let result = election(size as i32, &candidates, method, &settings)?;
+    Ok(draw(&result, shading, highlight_ties))
+}
+
+/// Renders the candidate that won most often at each point of the map, out of `repeats` elections
+/// of `num_voters` voters each, drawn at random from `seed`.
+///
+/// See `render` for `method_parameters` and `parse_settings` for the settings.
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen]
+pub fn render_sampled(
+    size: usize,
+    candidate_coords: Vec<f32>,
+    valence: Vec<f32>,
+    election_method: &str,
+    method_parameters: Vec<f32>,
+    tie_break: &str,
+    metric: &str,
+    voter_model: &str,
+    truncation: &str,
+    turnout: &str,
+    strategy: &str,
+    shape: &str,
+    electorate: Vec<f32>,
+    num_voters: usize,
+    repeats: usize,
+    seed: u32,
+) -> Result<Vec<u8>, JsValue> {
+    utils::set_panic_hook();
+
+    let mut registry = MethodRegistry::default();
+    let method = registry.configure(election_method, &method_parameters)?;
+    let settings = parse_settings(
+        tie_break,
+        metric,
+        voter_model,
+        truncation,
+        turnout,
+        strategy,
+        shape,
+        "grid",
+        &electorate,
+        &valence,
+    )?;
+    let sampling = Sampling {
+        num_voters,
+        repeats,
+        seed: seed as u64,
+    };
+    let candidates = parse_candidates(&candidate_coords)?;
+    let result = sample_election(size as i32, &candidates, method, &settings, &sampling)?;
+    Ok(draw_sampled(&result))
+}
+
+/// Computes the first round vote share of every candidate at each point of the map.
+///
+/// Returns `size * size * num_candidates` values, laid out as described in `ElectionResult`.
+/// Arguments are the same as for `render`.
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen]
+pub fn render_shares(
+    size: usize,
+    candidate_coords: Vec<f32>,
+    valence: Vec<f32>,
+    election_method: &str,
+    method_parameters: Vec<f32>,
+    metric: &str,
+    voter_model: &str,
+    truncation: &str,
+    turnout: &str,
+    strategy: &str,
+    shape: &str,
+    integration: &str,
+    electorate: Vec<f32>,
+) -> Result<Vec<f32>, JsValue> {
+    utils::set_panic_hook();
+
+    let mut registry = MethodRegistry::default();
+    let method = registry.configure(election_method, &method_parameters)?;
+    let settings = parse_settings(
+        "lowest",
+        metric,
+        voter_model,
+        truncation,
+        turnout,
+        strategy,
+        shape,
+        integration,
+        &electorate,
+        &valence,
+    )?;
+    let candidates = parse_candidates(&candidate_coords)?;
+    let mut result = election(size as i32, &candidates, method, &settings)?;
+    Ok(result.rounds.swap_remove(0))
+}
+
+/// Explains how the election was decided at pixel (x, y) of the map, as JSON.
+///
+/// The JSON is a serialized `Explanation`. Arguments are the same as for `render`.
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen]
+pub fn explain_point(
+    size: usize,
+    candidate_coords: Vec<f32>,
+    valence: Vec<f32>,
+    election_method: &str,
+    method_parameters: Vec<f32>,
+    tie_break: &str,
+    metric: &str,
+    voter_model: &str,
+    truncation: &str,
+    turnout: &str,
+    strategy: &str,
+    shape: &str,
+    integration: &str,
+    electorate: Vec<f32>,
+    x: i32,
+    y: i32,
+) -> Result<String, JsValue> {
+    utils::set_panic_hook();
+
+    let mut registry = MethodRegistry::default();
+    let method = registry.configure(election_method, &method_parameters)?;
+    let settings = parse_settings(
+        tie_break,
+        metric,
+        voter_model,
+        truncation,
+        turnout,
+        strategy,
+        shape,
+        integration,
+        &electorate,
+        &valence,
+    )?;
+    let candidates = parse_candidates(&candidate_coords)?;
+    let explanation = explain(size as i32, x, y, &candidates, method, &settings)?;
+    Ok(serde_json::to_string(&explanation).map_err(|e| e.to_string())?)
+}
+
+impl From<ElectionError> for JsValue {
+    fn from(error: ElectionError) -> JsValue {
+        JsValue::from_str(&error.to_string())
     }
+}
 
-    Ok(image.data)
+/// Converts a flat list of (x, y) coordinates into candidate positions.
+pub fn parse_candidates(coords: &[f32]) -> Result<Vec<Point>, ElectionError> {
+    if !coords.len().is_multiple_of(2) {
+        return Err(ElectionError::OddCoordinates(coords.len()));
+    }
+    Ok(coords
+        .chunks(2)
+        .map(|xy| Point::new(xy[0], xy[1]))
+        .collect())
 }
 
 #[wasm_bindgen]
 pub fn max_candidates() -> usize {
-    CANDIDATE_COLORS.len()
+    MAX_CANDIDATES
 }
 
 #[wasm_bindgen]
 pub fn candidate_color(i: usize) -> String {
-    let color = CANDIDATE_COLORS[i];
+    let color = palette(i + 1)[i];
     format!("rgb({}, {}, {})", color.r, color.g, color.b)
 }
 
-fn compute_votes<F>(
-    size: i32,
-    start: i32,
-    end: i32,
-    candidates: &[Point],
-    voting_method: F,
-) -> Vec<u8>
-where
-    F: Fn(Point, &[Point], &mut [u8]),
-{
-    let padded_size = (end - start) as i32;
-    let mut results = vec![0u8; padded_size.pow(2) as usize * candidates.len()];
+#[wasm_bindgen]
+pub fn num_election_methods() -> usize {
+    MethodRegistry::default().len()
+}
 
-    // Compute voting results at each individual point.
-    for x in start..end {
-        for y in start..end {
-            let at = Point::new(x as f32 / size as f32, y as f32 / size as f32);
-
-            let i = x - start;
-            let j = y - start;
-            let offset = ((i * padded_size + j) as usize) * candidates.len();
-
-            voting_method(
-                at,
-                &candidates,
-                &mut results[offset..offset + candidates.len()],
+#[wasm_bindgen]
+pub fn election_method_id(i: usize) -> Result<String, JsValue> {
+    Ok(MethodRegistry::default().get_index(i)?.id().to_string())
+}
+
+#[wasm_bindgen]
+pub fn election_method_name(i: usize) -> Result<String, JsValue> {
+    Ok(MethodRegistry::default().get_index(i)?.name().to_string())
+}
+
+/// The id of the type of ballot the `i`th election method asks for, see `BallotType::id`.
+#[wasm_bindgen]
+pub fn election_method_ballot_type(i: usize) -> Result<String, JsValue> {
+    Ok(MethodRegistry::default()
+        .get_index(i)?
+        .ballot_type()
+        .id()
+        .to_string())
+}
+
+/// The parameters of the `i`th election method, as a JSON list of `Parameter`s.
+#[wasm_bindgen]
+pub fn election_method_parameters(i: usize) -> Result<String, JsValue> {
+    let parameters = MethodRegistry::default().get_index(i)?.parameters();
+    Ok(serde_json::to_string(&parameters).map_err(|e| e.to_string())?)
+}
+
+/// Ballots cast by the voters at a single position along the hidden issues.
+struct Layer {
+    /// Share of the voters at this position.
+    weight: f32,
+    results: Vec<u8>,
+    /// Share of the voters at each location of the grid who turn out, or empty if they all do.
+    turnout: Vec<f32>,
+}
+
+impl Layer {
+    /// Weight of the `index`th ballot of the grid, for a location of weight `p` in the electorate.
+    fn weight_at(&self, index: usize, p: f32) -> f32 {
+        match self.turnout.get(index) {
+            Some(turnout) => p * self.weight * turnout,
+            None => p * self.weight,
+        }
+    }
+
+    /// The `size` x `size` part of this layer of a `padded_size` x `padded_size` grid, starting
+    /// `offset` pixels into it.
+    fn crop(
+        &self,
+        num_candidates: usize,
+        padded_size: usize,
+        offset: (usize, usize),
+        size: usize,
+    ) -> Layer {
+        let mut results = Vec::with_capacity(size.pow(2) * num_candidates);
+        let mut turnout = vec![];
+        for i in offset.0..offset.0 + size {
+            let column = i * padded_size + offset.1;
+            results.extend_from_slice(
+                &self.results[column * num_candidates..(column + size) * num_candidates],
             );
+            if !self.turnout.is_empty() {
+                turnout.extend_from_slice(&self.turnout[column..column + size]);
+            }
+        }
+        Layer {
+            weight: self.weight,
+            results,
+            turnout,
         }
     }
+}
 
-    results
+/// The pixels `[start; end)` of the window at `origin` of a `map_size` x `map_size` map.
+struct Grid {
+    map_size: i32,
+    origin: (i32, i32),
+    start: i32,
+    end: i32,
 }
 
-fn declare_winner<F>(
-    size: i32,
-    num_votes: &[f32],
-    candidates: &[Point],
-    select_winner: F,
-) -> Vec<u8>
-where
-    F: Fn(&[f32]) -> usize,
-{
-    let mut winners = vec![0u8; size.pow(2) as usize];
-    for x in 0..size {
-        for y in 0..size {
-            let i = ((x * size) + y) as usize * candidates.len();
-            let winner = select_winner(&num_votes[i..i + candidates.len()]);
-            winners[(x * size + y) as usize] = winner as u8;
-        }
+/// Casts a ballot at every pixel of `grid`, once for every position of voters along the hidden
+/// issues, and as many times as the voter model draws.
+///
+/// Voters adapt their ballots to `poll` if given, following `settings.strategy`.
+fn compute_votes(
+    grid: &Grid,
+    candidates: &Projection,
+    settings: &Settings,
+    method: &dyn ElectionMethod,
+    poll: Option<&Poll>,
+) -> Vec<Layer> {
+    let Grid {
+        map_size,
+        origin,
+        start,
+        end,
+    } = *grid;
+    let metric = settings.metric;
+    let model = settings.voter_model;
+    let draws = model.draws();
+    let adjusted = !settings.valence.is_empty() || model.is_random();
+    let padded_size = end - start;
+    let num_candidates = candidates.points.len();
+    let num_hidden = candidates.num_hidden();
+    let full_turnout = settings.turnout.is_full();
+
+    let mut layers = vec![];
+    let positions = candidates.positions.iter();
+    for ((hidden, weight), draw) in positions.flat_map(|p| (0..draws).map(move |d| (p, d))) {
+        // Compute voting results at each individual point, one column of the grid at a time.
+        let columns = parallel::map(padded_size as usize, |i| {
+            let x = start + i as i32;
+            let mut results = vec![0u8; padded_size as usize * num_candidates];
+            let mut turnout = vec![];
+            if !full_turnout {
+                turnout.resize(padded_size as usize, 0.0);
+            }
+            let mut measures = vec![0f32; num_candidates];
+            for y in start..end {
+                let at = Point::new(
+                    (origin.0 + x) as f32 / map_size as f32,
+                    (origin.1 + y) as f32 / map_size as f32,
+                );
+
+                let j = (y - start) as usize;
+                let offset = j * num_candidates;
+
+                for (c, measure) in measures.iter_mut().enumerate() {
+                    *measure = metric.measure(&at, &candidates.points[c]);
+                    let coordinates = &candidates.hidden[c * num_hidden..(c + 1) * num_hidden];
+                    for (voter, candidate) in hidden.iter().zip(coordinates) {
+                        *measure = metric.extend(*measure, voter - candidate);
+                    }
+                    if adjusted {
+                        let valence = settings.valence.get(c).copied().unwrap_or_default();
+                        *measure = valence.apply(metric.distance_of(*measure))
+                            + model.noise((origin.0 + x, origin.1 + y), map_size, draw, c);
+                    }
+                }
+                let voter = Voter::new(at, hidden, metric, &measures, adjusted);
+                if let Some(turnout) = turnout.get_mut(j) {
+                    *turnout = settings.turnout.of(&voter);
+                }
+                let ballot = &mut results[offset..offset + num_candidates];
+                method.cast_ballot(&voter, BallotMut::new(method.ballot_type(), ballot));
+                settings
+                    .truncation
+                    .apply(&voter, BallotMut::new(method.ballot_type(), ballot));
+                if let Some(poll) = poll {
+                    let ballot = BallotMut::new(method.ballot_type(), ballot);
+                    settings.strategy.apply(&voter, poll, ballot);
+                }
+            }
+            (results, turnout)
+        });
+        let (results, turnout): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+        let results = results.concat();
+        let turnout = turnout.concat();
+        layers.push(Layer {
+            weight: weight / draws as f32,
+            results,
+            turnout,
+        });
     }
-    winners
+
+    layers
 }
 
-fn sum_votes<F>(
+/// The ballots cast by the voters around every point of the map.
+///
+/// Ballots are cast on a grid padded by `range` pixels on each side, so that the neighbourhood
+/// of points on the edge of the map is fully populated.
+///
+/// The ballots may only cover a `size` x `size` window of a larger map, starting at pixel
+/// `origin`; pixel coordinates and point indices are then relative to the window.
+pub struct Ballots {
     size: i32,
-    candidates: &[Point],
+    map_size: i32,
+    origin: (i32, i32),
+    electorate: Electorate,
+    num_candidates: usize,
+    ballot_type: BallotType,
+    tie_break: TieBreak,
+    borda_counts: OnceLock<Vec<f32>>,
     start: i32,
     end: i32,
-    results: &[u8],
-    sample_locations: &[(i32, f32)],
-    count_votes: F,
-) -> Vec<f32>
-where
-    F: Fn(&mut [f32], &[u8], f32),
-{
-    let padded_size = (end - start) as i32;
-
-    // Sum up all the votes for the neighbour of each point.
-    let mut num_votes = vec![0f32; size.pow(2) as usize * candidates.len()];
-    for x in 0..size {
-        for y in start..end {
-            // Sum up all the votes along the x-neighbourhood.
-            let mut line_votes = vec![0f32; candidates.len()];
-            for (dx, p) in sample_locations.iter() {
-                let i = x + dx - start;
-                let j = y - start;
-                let offset = ((i * padded_size + j) as usize) * candidates.len();
-
-                count_votes(
-                    &mut line_votes,
-                    &results[offset..offset + candidates.len()],
-                    *p,
+    layers: Vec<Layer>,
+    kernels: Vec<Kernel>,
+    convolution: Convolution,
+    /// Ballots numbered before any others when tallying by type, `num_candidates` bytes each.
+    /// Windows recast from other ballots number them like those, so that they add up the ballots
+    /// they share in the same order.
+    known_types: Vec<u8>,
+    /// The weight of each distinct ballot around each point, once tallied, unless there are too
+    /// many of them to keep. Exact integration only yields this tally.
+    histogram: OnceLock<Option<Histogram>>,
+}
+
+impl Ballots {
+    /// Width and height of the map, or of the window onto the map.
+    pub fn size(&self) -> i32 {
+        self.size
+    }
+
+    /// The voters around each point.
+    pub fn electorate(&self) -> &Electorate {
+        &self.electorate
+    }
+
+    pub fn num_candidates(&self) -> usize {
+        self.num_candidates
+    }
+
+    pub fn ballot_type(&self) -> BallotType {
+        self.ballot_type
+    }
+
+    /// Weights the ballots with `kernels` instead, which must not reach further than the ones
+    /// the ballots were cast for.
+    fn set_kernels(&mut self, kernels: Vec<Kernel>) {
+        self.kernels = kernels;
+        self.borda_counts = OnceLock::new();
+        self.histogram = OnceLock::new();
+    }
+
+    /// The ballots tallied by type, which is done the first time they are counted.
+    fn histogram(&self) -> Option<&Histogram> {
+        self.histogram.get_or_init(|| self.tally()).as_ref()
+    }
+
+    /// Tallies the ballots of the grid by type around every point, unless there are too many
+    /// distinct ballots for the weights of all of them to fit in `MAX_WEIGHTS`.
+    fn tally(&self) -> Option<Histogram> {
+        let num_candidates = self.num_candidates;
+        let mut types = BallotTypes::new(num_candidates);
+        for ballot in self.known_types.chunks(num_candidates) {
+            types.number(ballot);
+        }
+        let numbers: Vec<Vec<u32>> = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let ballots = layer.results.chunks(num_candidates);
+                ballots.map(|ballot| types.number(ballot)).collect()
+            })
+            .collect();
+        let num_types = types.len();
+        if num_types * self.size.pow(2) as usize > MAX_WEIGHTS {
+            return None;
+        }
+
+        let weights = self.convolve(num_types, || {
+            |counts: &mut [f64], layer: usize, index: usize, p: f32| {
+                counts[numbers[layer][index] as usize] += p as f64;
+            }
+        });
+        let mut histogram = Histogram::new(types);
+        for counts in weights.chunks(num_types) {
+            histogram.push(counts.iter().enumerate().map(|(i, &p)| (i as u32, p)));
+        }
+        Some(histogram)
+    }
+
+    /// Sums up the ballots in the neighbourhood of every point of the map.
+    ///
+    /// `count_votes` is called with the vote totals to update, a single ballot and the weight of
+    /// that ballot. Returns the vote totals of every point, `num_candidates` values per point.
+    ///
+    /// The ballots are tallied by type the first time they are counted, so that every count only
+    /// visits the distinct ballots around each point, whatever the method.
+    ///
+    /// Panics if `B` doesn't match the type of the ballots that were cast.
+    pub fn sum_votes<'a, B, F>(&'a self, count_votes: F) -> Vec<f32>
+    where
+        B: Ballot<'a>,
+        F: Fn(&mut [f32], B, f32) + Sync,
+    {
+        assert_eq!(
+            B::TYPE,
+            self.ballot_type,
+            "ballots were cast as {:?}",
+            self.ballot_type
+        );
+        if let Some(histogram) = self.histogram() {
+            return histogram.sum_votes(&count_votes);
+        }
+        let num_candidates = self.num_candidates;
+        let count_votes = &count_votes;
+        self.convolve(num_candidates, || {
+            let mut ballot_votes = vec![0f32; num_candidates];
+            move |votes: &mut [f64], layer: usize, index: usize, p: f32| {
+                let offset = index * num_candidates;
+                let ballot = &self.layers[layer].results[offset..offset + num_candidates];
+                ballot_votes.iter_mut().for_each(|vote| *vote = 0.0);
+                count_votes(&mut ballot_votes, B::from_raw(ballot), p);
+                simd::add_widened(votes, &ballot_votes);
+            }
+        })
+    }
+
+    /// Sums up the ballots around a single point, like `sum_votes`, if they were tallied by type.
+    /// Otherwise, the ballots are only summed up for the whole map at once.
+    pub(crate) fn sum_votes_at<'a, B, F>(&'a self, point: usize, count_votes: F) -> Option<Vec<f32>>
+    where
+        B: Ballot<'a>,
+        F: Fn(&mut [f32], B, f32),
+    {
+        let histogram = self.histogram()?;
+        let mut votes = vec![0f32; self.num_candidates];
+        histogram.sum_votes_at(point, &mut votes, &count_votes);
+        Some(votes)
+    }
+
+    /// Convolves the grid of ballots with the electorate around every point, returning `width`
+    /// totals per point.
+    ///
+    /// `init` makes a function to count ballots, once for every thread that counts them. It is
+    /// called with the totals to update, the layer and index of a ballot on the grid, and the
+    /// weight of that ballot. Totals are added up in double precision, so that they agree within
+    /// `TIE_TOLERANCE` whichever order the voters are added up in, and however they are
+    /// convolved.
+    fn convolve<I, F>(&self, width: usize, init: I) -> Vec<f32>
+    where
+        I: Fn() -> F + Sync + Send,
+        F: FnMut(&mut [f64], usize, usize, f32),
+    {
+        let size = self.size;
+        let start = self.start;
+        let end = self.end;
+        let padded_size = end - start;
+
+        // Sum up all the votes for the neighbour of each point, one cluster of the electorate and
+        // one position along the hidden issues at a time. Correlated clusters also need the line
+        // sums beside the map, which are shifted onto the map.
+        let mut num_votes = vec![0f64; size.pow(2) as usize * width];
+        for ((l, layer), kernel) in self
+            .layers
+            .iter()
+            .enumerate()
+            .flat_map(|layer| self.kernels.iter().map(move |kernel| (layer, kernel)))
+        {
+            let (offset_x, offset_y) = kernel.offset;
+            let (line, column, shear) = match &kernel.weights {
+                Weights::Separable {
+                    line,
+                    column,
+                    shear,
+                } => (line, column, *shear),
+                Weights::Full(weights) => {
+                    self.convolve_2d(&init, width, l, weights, kernel.offset, &mut num_votes);
+                    continue;
+                }
+            };
+            let fft = match self.convolution {
+                Convolution::Auto => {
+                    let lines = (size + 2 * shear) as usize;
+                    let rows = padded_size as usize;
+                    let (line_len, column_len) = (line.len(), column.len());
+                    size >= FFT_MIN_SIZE
+                        && pays_off(
+                            width,
+                            self.num_candidates,
+                            lines,
+                            rows,
+                            line_len,
+                            column_len,
+                        )
+                }
+                Convolution::Direct => false,
+                Convolution::Fft => true,
+            };
+            if fft {
+                self.convolve_fft(&init, width, l, kernel, &mut num_votes);
+                continue;
+            }
+            let reach = column.iter().map(|&(dy, _, _)| dy.abs()).max().unwrap_or(0);
+            let y0 = (offset_y - reach).max(start);
+            let num_rows = ((size + offset_y + reach).min(end) - y0) as usize;
+            // The weights along y for each shift along x, the rows furthest down first, so that
+            // each point adds up the line sums in the same order as they were computed in.
+            let mut shifts: Vec<_> = column.iter().map(|&(_, shift, _)| shift).collect();
+            shifts.sort_unstable();
+            shifts.dedup();
+            let taps: Vec<(i32, Vec<(i32, f32)>)> = shifts
+                .into_iter()
+                .map(|shift| {
+                    let mut taps: Vec<_> = column
+                        .iter()
+                        .filter(|&&(_, s, _)| s == shift)
+                        .map(|&(dy, _, p)| (dy, p))
+                        .collect();
+                    taps.sort_by_key(|&(dy, _)| -dy);
+                    (shift, taps)
+                })
+                .collect();
+            for x in offset_x - shear..size + offset_x + shear {
+                // Sum up all the votes along the x-neighbourhood of each row. Only a few of the
+                // totals are counted along a line when there are many.
+                let line_votes = parallel::map_init(
+                    num_rows,
+                    || (init(), vec![0f64; width]),
+                    |(count, line_votes), row| {
+                        let j = y0 + row as i32 - start;
+                        for (dx, p) in line.iter() {
+                            let i = x + dx - start;
+                            let index = (i * padded_size + j) as usize;
+                            count(line_votes, l, index, layer.weight_at(index, *p));
+                        }
+                        let mut counted = vec![];
+                        for (i, votes) in line_votes.iter_mut().enumerate() {
+                            if *votes != 0.0 {
+                                counted.push((i, *votes));
+                                *votes = 0.0;
+                            }
+                        }
+                        counted
+                    },
                 );
+
+                // Add the summed votes to all points with the same x coordinate, give or take
+                // the shift, weighted by the distance along the y-axis. This stays a plain loop
+                // over the counted totals: a row is only as wide as the candidates when it is
+                // dense, too short for `simd` kernels to pay off, and sparse when it has a total
+                // per ballot type.
+                for (shift, taps) in taps.iter() {
+                    let xp = x + shift - offset_x;
+                    if xp < 0 || xp >= size {
+                        continue;
+                    }
+                    let column_size = size as usize * width;
+                    let points = &mut num_votes[xp as usize * column_size..][..column_size];
+                    parallel::for_each_chunk(points, width, |yp, votes| {
+                        for &(dy, p) in taps.iter() {
+                            let row = yp as i32 + offset_y - dy - y0;
+                            if row < 0 || row >= num_rows as i32 {
+                                continue;
+                            }
+                            for &(i, line_votes) in line_votes[row as usize].iter() {
+                                votes[i] += line_votes * p as f64;
+                            }
+                        }
+                    });
+                }
             }
+        }
+        num_votes.iter().map(|&votes| votes as f32).collect()
+    }
+
+    /// Adds the ballots of layer `l` around every point to `num_votes` like `convolve`, for a
+    /// separable `kernel`, by multiplying the Fourier transforms of the rows and then the columns
+    /// of the grid with those of the kernel.
+    ///
+    /// Ballots must be counted linearly in their weight. Totals are transformed two at a time,
+    /// as the real and imaginary parts of complex values, and as many pairs at once as fit in
+    /// `MAX_BATCH` values.
+    fn convolve_fft<I, F>(
+        &self,
+        init: &I,
+        width: usize,
+        l: usize,
+        kernel: &Kernel,
+        num_votes: &mut [f64],
+    ) where
+        I: Fn() -> F + Sync + Send,
+        F: FnMut(&mut [f64], usize, usize, f32),
+    {
+        let (line, column, shear) = match &kernel.weights {
+            Weights::Separable {
+                line,
+                column,
+                shear,
+            } => (line, column, *shear),
+            Weights::Full(_) => unreachable!("only separable kernels are transformed"),
+        };
+        let layer = &self.layers[l];
+        let size = self.size;
+        let start = self.start;
+        let padded_size = (self.end - start) as usize;
+        let (offset_x, offset_y) = kernel.offset;
+        let reach = column.iter().map(|&(dy, _, _)| dy.abs()).max().unwrap_or(0);
+
+        // Lines are summed up along x at `x0..x0 + num_lines`, for each row at `y0..y0 + num_rows`.
+        let x0 = offset_x - shear;
+        let num_lines = (size + 2 * shear) as usize;
+        let y0 = (offset_y - reach).max(start);
+        let num_rows = ((size + offset_y + reach).min(self.end) - y0) as usize;
+        let (min_dx, max_dx) = line
+            .iter()
+            .fold((0, 0), |(min, max), &(dx, _)| (dx.min(min), dx.max(max)));
+        let first = x0 - start + min_dx;
+        let last = x0 + num_lines as i32 - 1 - start + max_dx;
+        let row_fft = Fft::new(padded_len(padded_size, first, last));
+        let line_spectrum = correlation(&row_fft, line.iter().copied());
+        let first = offset_y - y0 - reach;
+        let last = size - 1 + offset_y - y0 + reach;
+        let column_fft = Fft::new(padded_len(num_rows, first, last));
+        let mut shifts: Vec<_> = column.iter().map(|&(_, shift, _)| shift).collect();
+        shifts.sort_unstable();
+        shifts.dedup();
+        let column_spectra: Vec<_> = shifts
+            .iter()
+            .map(|&shift| {
+                let taps = column.iter().filter(|&&(_, s, _)| s == shift);
+                let spectrum = correlation(&column_fft, taps.map(|&(dy, _, p)| (-dy, p)));
+                (shift, spectrum)
+            })
+            .collect();
+        let line_weight: f64 = line.iter().map(|&(_, p)| p.abs() as f64).sum();
+        let column_weight: f64 = column.iter().map(|&(_, _, p)| p.abs() as f64).sum();
+
+        let row_len = row_fft.len();
+        let column_len = column_fft.len();
+        let num_pairs = width.div_ceil(2);
+        let batch = (MAX_BATCH / (num_lines * column_len)).clamp(1, num_pairs);
+        let mut lines = vec![Complex::default(); batch * num_lines * column_len];
+        for first_pair in (0..num_pairs).step_by(batch) {
+            let pairs = batch.min(num_pairs - first_pair);
+            let channel = |pair: usize, votes: &[f64]| {
+                let c = 2 * (first_pair + pair);
+                Complex::new(votes[c], votes.get(c + 1).copied().unwrap_or_default())
+            };
 
-            // Add the summed votes to all points with the same x coordinate,
-            // weighted by the distance along the y-axis.
-            for (dy, p) in sample_locations.iter() {
-                let yp = y + dy;
-                if yp >= 0 && yp < size {
-                    for i in 0..candidates.len() {
-                        num_votes[((x * size) + yp) as usize * candidates.len() + i] +=
-                            line_votes[i] * p;
+            // Sum up the votes along x, one row at a time, keeping the line sums of each pair.
+            let row_sums = parallel::map_init(
+                num_rows,
+                || {
+                    let rows = vec![Complex::default(); pairs * row_len];
+                    (init(), vec![0f64; width], rows)
+                },
+                |(count, votes, rows), j| {
+                    let y = y0 + j as i32;
+                    let mut largest = 0f64;
+                    rows.iter_mut()
+                        .for_each(|value| *value = Complex::default());
+                    for i in 0..padded_size {
+                        let index = i * padded_size + (y - start) as usize;
+                        count(votes, l, index, layer.weight_at(index, 1.0));
+                        for pair in 0..pairs {
+                            let value = channel(pair, votes);
+                            largest = largest.max(value.re.abs()).max(value.im.abs());
+                            rows[pair * row_len + i] = value;
+                        }
+                        votes.iter_mut().for_each(|vote| *vote = 0.0);
+                    }
+                    let mut sums = Vec::with_capacity(pairs * num_lines);
+                    for row in rows.chunks_mut(row_len) {
+                        row_fft.forward(row);
+                        simd::mul_complex(row, &line_spectrum);
+                        row_fft.inverse(row);
+                        let first = (x0 - start) as usize;
+                        sums.extend_from_slice(&row[first..first + num_lines]);
                     }
+                    (largest, sums)
+                },
+            );
+            let mut largest = 0f64;
+            lines
+                .iter_mut()
+                .for_each(|value| *value = Complex::default());
+            for (j, (row_largest, sums)) in row_sums.into_iter().enumerate() {
+                largest = largest.max(row_largest);
+                for (k, sum) in sums.into_iter().enumerate() {
+                    lines[k * column_len + j] = sum;
                 }
             }
+
+            // Add up the line sums along y, shifted along x, for each column of points.
+            let threshold = FLUSH * largest * line_weight * column_weight;
+            let flush = |value: f64| if value.abs() <= threshold { 0.0 } else { value };
+            let lines = &mut lines[..pairs * num_lines * column_len];
+            parallel::for_each_chunk(lines, column_len, |_, line| column_fft.forward(line));
+            for (pair, lines) in lines.chunks(num_lines * column_len).enumerate() {
+                let c = 2 * (first_pair + pair);
+                let column_size = size as usize * width;
+                parallel::for_each_chunk(num_votes, column_size, |xp, points| {
+                    let xp = xp as i32;
+                    let mut sums = vec![Complex::default(); column_len];
+                    for (shift, spectrum) in column_spectra.iter() {
+                        let k = (xp - shift + offset_x - x0) as usize;
+                        let line = &lines[k * column_len..(k + 1) * column_len];
+                        simd::mul_add_complex(&mut sums, line, spectrum);
+                    }
+                    column_fft.inverse(&mut sums);
+                    for (yp, votes) in points.chunks_mut(width).enumerate() {
+                        let sum = sums[yp + (offset_y - y0) as usize];
+                        votes[c] += flush(sum.re);
+                        if c + 1 < width {
+                            votes[c + 1] += flush(sum.im);
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    /// Adds the ballots of layer `l` around every point to `num_votes`, weighting each voter
+    /// individually.
+    fn convolve_2d<I, F>(
+        &self,
+        init: &I,
+        width: usize,
+        l: usize,
+        weights: &[(i32, i32, f32)],
+        offset: (i32, i32),
+        num_votes: &mut [f64],
+    ) where
+        I: Fn() -> F + Sync + Send,
+        F: FnMut(&mut [f64], usize, usize, f32),
+    {
+        let layer = &self.layers[l];
+        let padded_size = self.end - self.start;
+        let column_size = self.size as usize * width;
+        parallel::for_each_chunk(num_votes, column_size, |x, points| {
+            let mut count = init();
+            let x = x as i32;
+            for (y, votes) in points.chunks_mut(width).enumerate() {
+                for &(dx, dy, p) in weights.iter() {
+                    let i = x + offset.0 + dx - self.start;
+                    let j = y as i32 + offset.1 + dy - self.start;
+                    let index = (i * padded_size + j) as usize;
+                    count(votes, l, index, layer.weight_at(index, p));
+                }
+            }
+        });
+    }
+
+    /// Picks the candidate closest to `goal` at a single point, breaking ties as configured.
+    ///
+    /// `point` is the index of the point on the map and `votes` its vote totals. Candidates set in
+    /// the `excluded` bitmask are ignored. Methods with several rounds pass the round and the vote
+    /// totals of the previous round at this point.
+    pub fn pick(
+        &self,
+        goal: Goal,
+        point: usize,
+        votes: &[f32],
+        excluded: u64,
+        round: usize,
+        previous: Option<&[f32]>,
+    ) -> Outcome {
+        let leaders = goal.leaders(votes, excluded);
+        if leaders.count_ones() <= 1 {
+            // Without any valid vote totals, fall back to the first candidate.
+            let winner = if leaders == 0 {
+                0
+            } else {
+                leaders.trailing_zeros() as usize
+            };
+            return Outcome {
+                winner,
+                tied: false,
+            };
+        }
+
+        let borda = match self.tie_break {
+            TieBreak::Borda if self.ballot_type == BallotType::Ranking => {
+                let counts = self.borda_counts.get_or_init(|| method::borda_count(self));
+                let i = point * self.num_candidates;
+                Some(&counts[i..i + self.num_candidates])
+            }
+            _ => None,
+        };
+        // Use the index on the whole map, so that random tie breaks don't depend on the window.
+        let (x, y) = (point as i32 / self.size, point as i32 % self.size);
+        let map_point =
+            (self.origin.0 + x) as i64 * self.map_size as i64 + (self.origin.1 + y) as i64;
+        let context = TieContext {
+            point: map_point as usize,
+            round,
+            previous,
+            borda,
+        };
+        Outcome {
+            winner: self.tie_break.choose(goal, leaders, &context),
+            tied: true,
         }
     }
-    num_votes
+
+    /// Selects the winner at each point of the map from its vote totals.
+    pub fn declare_winners(&self, num_votes: &[f32], goal: Goal) -> Vec<Outcome> {
+        let num_candidates = self.num_candidates;
+        num_votes
+            .chunks(num_candidates)
+            .enumerate()
+            .map(|(point, votes)| self.pick(goal, point, votes, 0, 0, None))
+            .collect()
+    }
+
+    /// The total weight of the ballots around each point, in the first of the `num_candidates`
+    /// values of each point.
+    fn count_ballots(&self) -> Vec<f32> {
+        fn count<B>(votes: &mut [f32], _: B, p: f32) {
+            votes[0] += p;
+        }
+        match self.ballot_type {
+            BallotType::Single => self.sum_votes(count::<Single<&[u8]>>),
+            BallotType::Ranking => self.sum_votes(count::<Ranking<&[u8]>>),
+            BallotType::Approval => self.sum_votes(count::<ApprovalBallot<&[u8]>>),
+        }
+    }
+
+    /// Share of the electorate around each point that turns out to vote.
+    fn turnout(&self) -> Vec<f32> {
+        let num_points = self.size.pow(2) as usize;
+        if self.layers.iter().all(|layer| layer.turnout.is_empty()) {
+            return vec![1.0; num_points];
+        }
+        // Every point has the same electorate, whether it turns out or not.
+        let layer_weight: f32 = self.layers.iter().map(|layer| layer.weight).sum();
+        let kernel_weight: f32 = self.kernels.iter().map(Kernel::total_weight).sum();
+        let electorate = layer_weight * kernel_weight;
+        // Count the voters rather than their ballots, which are tallied by type differently for
+        // each method.
+        let voters = self.convolve(1, || {
+            |voters: &mut [f64], _, _, p: f32| voters[0] += p as f64
+        });
+        voters.iter().map(|&voters| voters / electorate).collect()
+    }
+
+    /// Share of the ballots around each point that rank none of the candidates left in the
+    /// running there, i.e. that aren't set in the `eliminated` bitmask of the point.
+    ///
+    /// Only truncated ranked or single mark ballots run out, so this skips counting for any other
+    /// ballots.
+    fn exhausted(&self, truncation: Truncation, eliminated: &[u64]) -> Vec<f32> {
+        if truncation == Truncation::Full || self.ballot_type == BallotType::Approval {
+            return vec![0.0; eliminated.len()];
+        }
+        let num_candidates = self.num_candidates;
+        let total = self.count_ballots();
+        let mut exhausted = vec![0f32; eliminated.len()];
+        let mut sets = eliminated.to_vec();
+        sets.sort_unstable();
+        sets.dedup();
+        for set in sets {
+            let num_exhausted = if self.ballot_type == BallotType::Single {
+                self.sum_votes(|votes, ballot: Single<&[u8]>, p| {
+                    if ballot.choice().is_none_or(|c| (1 << c) & set != 0) {
+                        votes[0] += p;
+                    }
+                })
+            } else {
+                self.sum_votes(|votes, ballot: Ranking<&[u8]>, p| {
+                    if ballot.top_choices(set).next().is_none() {
+                        votes[0] += p;
+                    }
+                })
+            };
+            for (point, share) in exhausted.iter_mut().enumerate() {
+                let i = point * num_candidates;
+                if eliminated[point] == set && total[i] > 0.0 {
+                    *share = num_exhausted[i] / total[i];
+                }
+            }
+        }
+        exhausted
+    }
 }
 
-pub fn election(size: i32, candidates: &[Point], election_method: &str) -> Vec<u8> {
-    let sigma = 0.5f32 / 1.5;
-    let num_sigma = 3.0;
+fn validate(size: i32, candidates: &[Point], settings: &Settings) -> Result<(), ElectionError> {
+    if size <= 0 {
+        return Err(ElectionError::ZeroSize);
+    }
+    if candidates.is_empty() {
+        return Err(ElectionError::NoCandidates);
+    }
+    if candidates.len() > MAX_CANDIDATES {
+        return Err(ElectionError::TooManyCandidates {
+            count: candidates.len(),
+            max: MAX_CANDIDATES,
+        });
+    }
+    if let Some(i) = candidates
+        .iter()
+        .position(|c| !(c.x.is_finite() && c.y.is_finite()))
+    {
+        return Err(ElectionError::NonFiniteCoordinate(i));
+    }
+    if !settings.valence.is_empty() && settings.valence.len() != candidates.len() {
+        return Err(ElectionError::ValenceMismatch {
+            count: settings.valence.len(),
+            num_candidates: candidates.len(),
+        });
+    }
+    if let Some(i) = settings.valence.iter().position(|valence| {
+        !(valence.bonus.is_finite() && valence.quality > 0.0 && valence.quality.is_finite())
+    }) {
+        return Err(ElectionError::InvalidValence(i));
+    }
+    if let VoterModel::Logit { temperature, draws } = settings.voter_model {
+        if !(temperature > 0.0 && temperature.is_finite()) {
+            return Err(ElectionError::InvalidTemperature(temperature));
+        }
+        if draws == 0 || draws > MAX_DRAWS {
+            return Err(ElectionError::InvalidDraws {
+                draws,
+                max: MAX_DRAWS,
+            });
+        }
+    }
+    let share = settings.strategic_share;
+    if !(0.0..=1.0).contains(&share) {
+        return Err(ElectionError::InvalidStrategicShare(share));
+    }
+    match settings.truncation {
+        Truncation::TopK(0) => return Err(ElectionError::InvalidTruncation(settings.truncation)),
+        Truncation::Radius(radius) if radius.is_nan() || radius <= 0.0 => {
+            return Err(ElectionError::InvalidTruncation(settings.truncation));
+        }
+        _ => {}
+    }
+    let Turnout {
+        alienation,
+        indifference,
+    } = settings.turnout;
+    if let Some(alienation) = alienation.filter(|a| a.is_nan() || *a <= 0.0) {
+        return Err(ElectionError::InvalidAlienation(alienation));
+    }
+    if let Some(indifference) = indifference.filter(|i| i.is_nan() || *i <= 0.0) {
+        return Err(ElectionError::InvalidIndifference(indifference));
+    }
+    let electorate = &settings.electorate;
+    if let Some(i) = electorate.clusters.iter().position(|cluster| {
+        let valid_weight = cluster.weight >= 0.0 && cluster.weight.is_finite();
+        !valid_weight || !cluster.offset.x.is_finite() || !cluster.offset.y.is_finite()
+    }) {
+        return Err(ElectionError::InvalidCluster(i));
+    }
+    if electorate.total_weight() <= 0.0 {
+        return Err(ElectionError::EmptyElectorate);
+    }
+    for cluster in electorate.clusters.iter() {
+        let Covariance {
+            sigma_x,
+            sigma_y,
+            correlation,
+        } = cluster.covariance;
+        if let Some(&sigma) = [sigma_x, sigma_y]
+            .iter()
+            .find(|sigma| !(**sigma > 0.0 && sigma.is_finite()))
+        {
+            return Err(ElectionError::InvalidSigma(sigma));
+        }
+        if correlation.is_nan() || correlation.abs() >= 1.0 {
+            return Err(ElectionError::InvalidCorrelation(correlation));
+        }
+    }
+    if !(settings.num_sigma > 0.0 && settings.num_sigma.is_finite()) {
+        return Err(ElectionError::InvalidNumSigma(settings.num_sigma));
+    }
+    match settings.metric {
+        Metric::Minkowski { p } if !(p >= 1.0 && p.is_finite()) => {
+            return Err(ElectionError::InvalidMinkowskiOrder(p));
+        }
+        Metric::Salience { x, y } if !(x > 0.0 && x.is_finite() && y > 0.0 && y.is_finite()) => {
+            return Err(ElectionError::InvalidSalience { x, y });
+        }
+        _ => {}
+    }
+    let spread = electorate
+        .clusters
+        .iter()
+        .map(|cluster| {
+            let offset = cluster.offset.x.abs().max(cluster.offset.y.abs());
+            offset + cluster.reach_per_sigma() * settings.num_sigma
+        })
+        .fold(0.0, f32::max);
+    if spread > MAX_SPREAD {
+        return Err(ElectionError::SpreadTooLarge {
+            spread,
+            max: MAX_SPREAD,
+        });
+    }
+    Ok(())
+}
+
+/// Casts and weights the sincere ballots for a `size` x `size` window at `origin` of the map.
+fn cast_ballots(
+    map_size: i32,
+    origin: (i32, i32),
+    size: i32,
+    candidates: &Projection,
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Ballots {
+    if settings.integration == Integration::Exact {
+        let histogram =
+            region::histogram(map_size, origin, size, &candidates.points, method, settings);
+        return Ballots {
+            size,
+            map_size,
+            origin,
+            electorate: settings.electorate.clone(),
+            num_candidates: candidates.points.len(),
+            ballot_type: method.ballot_type(),
+            tie_break: settings.tie_break,
+            borda_counts: OnceLock::new(),
+            start: 0,
+            end: 0,
+            layers: vec![],
+            kernels: vec![],
+            convolution: settings.convolution,
+            known_types: vec![],
+            histogram: OnceLock::from(Some(histogram)),
+        };
+    }
+
+    // Neighbourhood weighting.
+    let electorate = &settings.electorate;
+    let total_weight = electorate.total_weight();
+    let kernels: Vec<Kernel> = electorate
+        .clusters
+        .iter()
+        .filter(|cluster| cluster.weight > 0.0)
+        .map(|cluster| {
+            let weight = cluster.weight / total_weight;
+            Kernel::new(map_size, cluster, weight, settings.num_sigma)
+        })
+        .collect();
 
-    // The vote map is [0; 1], so we need to compute votes in [-num_sigma * sigma; 1 + num_sigma * sigma].
-    let range = (size as f32 * sigma * num_sigma) as i32;
+    // The vote map is [0; 1], so we need to compute votes in [-num_sigma * sigma; 1 + num_sigma * sigma],
+    // around the center of each cluster.
+    let range = kernels.iter().map(|kernel| kernel.range).max().unwrap();
     let start = -range;
     let end = size + range;
 
     // Compute voting results at each individual point.
-    let results = match election_method {
-        "plurality" => compute_votes(size, start, end, &candidates, vote_plurality),
-        "approval" => compute_votes(size, start, end, &candidates, vote_close),
-        "borda" | "condorcet" | "hare" => compute_votes(size, start, end, &candidates, vote_rank),
-        _ => unreachable!("unsupported election method {}", election_method),
+    let grid = Grid {
+        map_size,
+        origin,
+        start,
+        end,
     };
+    let layers = compute_votes(&grid, candidates, settings, method, None);
 
-    // Neighbourhood weighting.
-    let mut sample_locations = vec![];
-    for x in -range..range {
-        let p = normal_pdf(0f32, sigma, x as f32 / size as f32);
-        sample_locations.push((x, p));
+    Ballots {
+        size,
+        map_size,
+        origin,
+        electorate: settings.electorate.clone(),
+        num_candidates: candidates.points.len(),
+        ballot_type: method.ballot_type(),
+        tie_break: settings.tie_break,
+        borda_counts: OnceLock::new(),
+        start,
+        end,
+        layers,
+        kernels,
+        convolution: settings.convolution,
+        known_types: vec![],
+        histogram: OnceLock::new(),
     }
+}
 
-    // Sum up all votes weighted by their neighborhouds.
-    let num_votes = match election_method {
-        "hare" => sum_votes(
-            size,
-            candidates,
-            start,
-            end,
-            &results,
-            &sample_locations,
-            |line_votes, results, p| {
-                let mut winner = 0;
-                let mut best_rank = 255;
-                for c in 0..candidates.len() {
-                    let rank = results[c];
-                    if rank < best_rank {
-                        best_rank = rank;
-                        winner = c;
-                    }
-                }
-                line_votes[winner] += p;
-            },
-        ),
-        _ => sum_votes(
-            size,
-            candidates,
-            start,
-            end,
-            &results,
-            &sample_locations,
-            |line_votes, results, p| {
-                for c in 0..candidates.len() {
-                    line_votes[c] += results[c] as f32 * p;
-                }
-            },
-        ),
+/// Casts the ballots for a `size` x `size` window at `origin` of the map, which must lie within
+/// the window of the `sincere` ballots, with the share of strategic voters adapting theirs to
+/// `poll`.
+///
+/// Only the strategic ballots are cast again; the other voters keep their ballots from
+/// `sincere`, and all of them keep its weights.
+fn recast_ballots(
+    sincere: &Ballots,
+    origin: (i32, i32),
+    size: i32,
+    candidates: &Projection,
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+    poll: &Poll,
+) -> Ballots {
+    let range = -sincere.start;
+    let (start, end) = (-range, size + range);
+    let grid = Grid {
+        map_size: sincere.map_size,
+        origin,
+        start,
+        end,
+    };
+    let share = settings.strategic_share;
+    let strategic = compute_votes(&grid, candidates, settings, method, Some(poll));
+    let weighted = |layers: Vec<Layer>, share: f32| {
+        layers.into_iter().map(move |layer| Layer {
+            weight: layer.weight * share,
+            ..layer
+        })
+    };
+    let layers = if share < 1.0 {
+        let padded_size = (sincere.end - sincere.start) as usize;
+        let offset = (
+            (origin.0 - sincere.origin.0) as usize,
+            (origin.1 - sincere.origin.1) as usize,
+        );
+        let crop = |layer: &Layer| {
+            layer.crop(
+                sincere.num_candidates,
+                padded_size,
+                offset,
+                (end - start) as usize,
+            )
+        };
+        let sincere = sincere.layers.iter().map(crop).collect();
+        weighted(sincere, 1.0 - share)
+            .chain(weighted(strategic, share))
+            .collect()
+    } else {
+        strategic
     };
 
-    if election_method == "hare" {
-        let undecided = 255;
-        let mut winners = vec![undecided; size.pow(2) as usize];
-
-        let mut votes_with_eliminated_candidates = vec![None; 2usize.pow(candidates.len() as u32)];
-        for x in 0..size {
-            for y in 0..size {
-                // First, check if we already have a majority winner.
-                let vote_i = ((x * size) + y) as usize * candidates.len();
-                let votes = &num_votes[vote_i..vote_i + candidates.len()];
-
-                let maybe_winner = max_vote_candidate(&votes);
-                let vote_sum: f32 = votes.iter().sum();
-                if votes[maybe_winner] >= 0.5 * vote_sum {
-                    // If one candidate has more than half the ballots, that candidate wins.
-                    winners[(x * size + y) as usize] = maybe_winner as u8;
-                    continue;
-                }
+    Ballots {
+        size,
+        map_size: sincere.map_size,
+        origin,
+        electorate: sincere.electorate.clone(),
+        num_candidates: sincere.num_candidates,
+        ballot_type: sincere.ballot_type,
+        tie_break: sincere.tie_break,
+        borda_counts: OnceLock::new(),
+        start,
+        end,
+        layers,
+        kernels: sincere.kernels.clone(),
+        convolution: sincere.convolution,
+        known_types: sincere
+            .histogram()
+            .map_or_else(Vec::new, |histogram| histogram.ballots().to_vec()),
+        histogram: OnceLock::new(),
+    }
+}
 
-                // Otherwise, the candidate with the fewest ballots is eliminated and we vote again.
-                let mut eliminated = 1 << min_vote_candidate(&votes);
-
-                for _ in 0..candidates.len() {
-                    let num_votes = votes_with_eliminated_candidates[eliminated]
-                        .get_or_insert_with(|| {
-                            sum_votes(
-                                size,
-                                candidates,
-                                start,
-                                end,
-                                &results,
-                                &sample_locations,
-                                |line_votes, results, p| {
-                                    let mut winner = 0;
-                                    let mut best_rank = 255;
-                                    for c in 0..candidates.len() {
-                                        let rank = results[c];
-                                        if rank < best_rank && (1 << c) & eliminated == 0 {
-                                            best_rank = rank;
-                                            winner = c;
-                                        }
-                                    }
-                                    line_votes[winner] += p;
-                                },
-                            )
-                        });
-                    let votes = &num_votes[vote_i..vote_i + candidates.len()];
-
-                    // Check if we have a winner.
-                    let maybe_winner = max_vote_candidate(&votes);
-                    let vote_sum: f32 = votes.iter().sum();
-                    if votes[maybe_winner] >= 0.5 * vote_sum {
-                        // If one candidate has more than half the ballots, that candidate wins.
-                        winners[(x * size + y) as usize] = maybe_winner as u8;
-                        break;
-                    } else {
-                        // Otherwise, the candidate with the fewest ballots is eliminated.
-                        let mut worst_candidate = 255;
-                        let mut min_votes = 1e9;
-                        for c in 0..candidates.len() {
-                            let v = votes[c];
-                            if v < min_votes && (1 << c) & eliminated == 0 {
-                                min_votes = v;
-                                worst_candidate = c;
-                            }
-                        }
-                        eliminated |= 1 << worst_candidate;
-                    }
-                }
-            }
+/// Simulates the election at every point of a `size` x `size` map.
+pub fn election(
+    size: i32,
+    candidates: &[Point],
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Result<ElectionResult, ElectionError> {
+    validate(size, candidates, settings)?;
+    run_election(size, &Projection::plane(candidates), method, settings)
+}
+
+/// Simulates the election at every point of a `size` x `size` map showing `slice` of an issue
+/// space with more than two dimensions.
+///
+/// Candidates have a coordinate for every issue of the space. The electorate is integrated over
+/// the hidden issues as well as over the plane of the map.
+pub fn election_in_slice(
+    size: i32,
+    candidates: &[Vec<f32>],
+    slice: &Slice,
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Result<ElectionResult, ElectionError> {
+    let projection = project(size, candidates, slice, settings)?;
+    run_election(size, &projection, method, settings)
+}
+
+/// Checks the election in `slice` and projects its candidates onto the map.
+fn project(
+    size: i32,
+    candidates: &[Vec<f32>],
+    slice: &Slice,
+    settings: &Settings,
+) -> Result<Projection, ElectionError> {
+    slice.validate(candidates)?;
+    let projection = Projection::new(candidates, slice, settings.num_sigma);
+    validate(size, &projection.points, settings)?;
+    let num_hidden = projection.num_hidden();
+    if let Some(i) = (0..candidates.len()).find(|&i| {
+        projection.hidden[i * num_hidden..(i + 1) * num_hidden]
+            .iter()
+            .any(|c| !c.is_finite())
+    }) {
+        return Err(ElectionError::NonFiniteCoordinate(i));
+    }
+    Ok(projection)
+}
+
+fn run_election(
+    size: i32,
+    candidates: &Projection,
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Result<ElectionResult, ElectionError> {
+    check_strategy(method, settings)?;
+    check_integration(method, settings, candidates)?;
+    let ballots = cast_ballots(size, (0, 0), size, candidates, method, settings);
+
+    // Sum up all votes weighted by their neighborhouds.
+    let num_votes = method.tally(&ballots);
+
+    // Select the winner of the election for each point.
+    let winners = method.select_winners(&ballots, &num_votes);
+    let (num_votes, winners, exhausted) = match settings.strategy {
+        Strategy::Sincere => {
+            let exhausted = ballots.exhausted(settings.truncation, &winners.eliminated);
+            (num_votes, winners, exhausted)
         }
+        _ => strategy::vote(candidates, method, settings, &ballots, &num_votes, &winners),
+    };
+    Ok(ElectionResult::new(
+        size as usize,
+        ballots.electorate().clone(),
+        method.id(),
+        num_votes,
+        winners,
+        exhausted,
+        ballots.turnout(),
+    ))
+}
 
-        winners
+fn check_strategy(method: &dyn ElectionMethod, settings: &Settings) -> Result<(), ElectionError> {
+    if settings.strategy.applies_to(method.ballot_type()) {
+        Ok(())
     } else {
-        // Select the winner of the election for each point.
-        match election_method {
-            "plurality" | "approval" => {
-                declare_winner(size, &num_votes, candidates, max_vote_candidate)
-            }
-            "borda" => declare_winner(size, &num_votes, candidates, min_vote_candidate),
-            "condorcet" => unimplemented!(
-                "condorcet; must select candidate that would defeat all others one-to-one"
-            ),
-            _ => unreachable!("unsupported election method {}", election_method),
-        }
+        Err(ElectionError::StrategyMismatch {
+            strategy: settings.strategy,
+            ballot_type: method.ballot_type(),
+        })
+    }
+}
+
+/// Checks that the ballots of the election can be integrated as `settings.integration` asks.
+fn check_integration(
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+    candidates: &Projection,
+) -> Result<(), ElectionError> {
+    if settings.integration == Integration::Grid {
+        return Ok(());
+    }
+    let unsupported = if method.ballot_type() == BallotType::Approval {
+        Some("approval ballots")
+    } else if settings.metric != Metric::Euclidean {
+        Some("non-Euclidean metrics")
+    } else if !settings.valence.is_empty() {
+        Some("valence")
+    } else if settings.voter_model.is_random() {
+        Some("random voter models")
+    } else if let Truncation::Radius(_) = settings.truncation {
+        Some("truncation by radius")
+    } else if !settings.turnout.is_full() {
+        Some("partial turnout")
+    } else if settings.strategy != Strategy::Sincere {
+        Some("strategic voting")
+    } else if settings
+        .electorate
+        .clusters
+        .iter()
+        .any(|cluster| cluster.shape != Shape::Gaussian)
+    {
+        Some("non-Gaussian clusters")
+    } else if candidates.num_hidden() > 0 {
+        Some("hidden issues")
+    } else {
+        None
+    };
+    if let Some(feature) = unsupported {
+        return Err(ElectionError::ExactUnsupported(feature));
+    }
+    if candidates.points.len() > MAX_EXACT_CANDIDATES {
+        return Err(ElectionError::TooManyExactCandidates {
+            count: candidates.points.len(),
+            max: MAX_EXACT_CANDIDATES,
+        });
+    }
+    Ok(())
+}
+
+/// Explains how the election was decided at pixel (x, y) of a `size` x `size` map.
+///
+/// The outcome is the same as the one `election` finds at that pixel, but only the electorate
+/// around that pixel is simulated. The pixel may be outside of the map.
+pub fn explain(
+    size: i32,
+    x: i32,
+    y: i32,
+    candidates: &[Point],
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Result<Explanation, ElectionError> {
+    validate(size, candidates, settings)?;
+    run_explain(
+        size,
+        (x, y),
+        &Projection::plane(candidates),
+        method,
+        settings,
+    )
+}
+
+/// Explains how the election was decided at pixel (x, y) of a map showing `slice`, see
+/// `election_in_slice`.
+pub fn explain_in_slice(
+    size: i32,
+    x: i32,
+    y: i32,
+    candidates: &[Vec<f32>],
+    slice: &Slice,
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Result<Explanation, ElectionError> {
+    let projection = project(size, candidates, slice, settings)?;
+    run_explain(size, (x, y), &projection, method, settings)
+}
+
+fn run_explain(
+    size: i32,
+    at: (i32, i32),
+    candidates: &Projection,
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Result<Explanation, ElectionError> {
+    check_strategy(method, settings)?;
+    check_integration(method, settings, candidates)?;
+    let mut ballots = cast_ballots(size, at, 1, candidates, method, settings);
+    let mut num_votes = method.tally(&ballots);
+    if settings.strategy != Strategy::Sincere {
+        // Poll the sincere election at this point first.
+        let winners = method.select_winners(&ballots, &num_votes);
+        let poll = Poll::at_each_point(&num_votes, &winners)[0];
+        ballots = recast_ballots(&ballots, at, 1, candidates, method, settings, &poll);
+        num_votes = method.tally(&ballots);
     }
+    Ok(method.explain(&ballots, &num_votes))
 }
@@ -0,0 +1,636 @@
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::ballot::{Approval as ApprovalBallot, BallotMut, BallotType, Ranking, Single};
+use crate::parallel;
+use crate::{Ballots, ElectionError, Explanation, Goal, Outcome, Voter, Winners};
+
+/// A tunable parameter of an election method, together with its current value.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize)]
+pub struct Parameter {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub value: f32,
+}
+
+/// An election method that can be simulated on the vote map.
+///
+/// An election runs in three steps:
+///
+/// 1. `cast_ballot` fills in the ballot of a voter at a single location.
+/// 2. `tally` sums up the ballots around each point of the map into vote totals, usually with
+///    `Ballots::sum_votes`.
+/// 3. `select_winners` picks the winner at each point of the map from the vote totals. Methods
+///    with several rounds can count the ballots again, and report the totals of each round.
+///
+/// `explain` reruns the last step for a map of a single point, keeping track of how the winner
+/// was picked.
+///
+/// Methods are shared between threads, which cast the ballots of different parts of the map with
+/// the `parallel` feature.
+pub trait ElectionMethod: Send + Sync {
+    /// Stable identifier, used to select the method from the web page.
+    fn id(&self) -> &'static str;
+
+    /// Human readable name.
+    fn name(&self) -> &'static str;
+
+    fn ballot_type(&self) -> BallotType;
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![]
+    }
+
+    /// Changes the value of the parameter with this id, one of those listed by `parameters`.
+    fn set_parameter(&mut self, id: &str, value: f32) -> Result<(), ElectionError> {
+        Err(ElectionError::UnknownParameter {
+            method: self.id(),
+            parameter: id.to_string(),
+            value,
+        })
+    }
+
+    /// Fills in a blank ballot of type `ballot_type()`.
+    fn cast_ballot(&self, voter: &Voter, ballot: BallotMut);
+
+    fn tally(&self, ballots: &Ballots) -> Vec<f32>;
+
+    fn select_winners(&self, ballots: &Ballots, num_votes: &[f32]) -> Winners;
+
+    /// Explains how the winner of a single point map was picked.
+    ///
+    /// By default, lists the vote totals of each round reported by `select_winners`.
+    fn explain(&self, ballots: &Ballots, num_votes: &[f32]) -> Explanation {
+        let winners = self.select_winners(ballots, num_votes);
+        let totals: Vec<&[f32]> = std::iter::once(num_votes)
+            .chain(winners.later_rounds.iter().map(|totals| &totals[..]))
+            .take(winners.num_rounds[0])
+            .collect();
+        Explanation::from_rounds(winners.outcomes[0], &totals, &[])
+    }
+}
+
+fn vote_plurality(voter: &Voter, mut ballot: Single<&mut [u8]>) {
+    let mut closest_i = 100000000;
+    let mut closest_dist = f32::MAX;
+    for i in 0..voter.num_candidates() {
+        let dist = voter.measure(i);
+        if dist < closest_dist {
+            closest_dist = dist;
+            closest_i = i;
+        }
+    }
+    ballot.choose(closest_i);
+}
+
+fn vote_close(voter: &Voter, max_dist: f32, mut ballot: ApprovalBallot<&mut [u8]>) {
+    for i in 0..voter.num_candidates() {
+        if voter.is_within(i, max_dist) {
+            ballot.approve(i);
+        }
+    }
+}
+
+/// Ranks all candidates by distance; candidates at exactly the same distance share a rank.
+fn vote_rank(voter: &Voter, mut ballot: Ranking<&mut [u8]>) {
+    for i in 0..voter.num_candidates() {
+        let dist = voter.measure(i);
+        let num_closer = (0..voter.num_candidates())
+            .filter(|&other| voter.measure(other) < dist)
+            .count();
+        ballot.set_rank(i, (1 + num_closer) as u8);
+    }
+}
+
+/// First past the post: every voter marks their closest candidate.
+pub struct Plurality;
+
+impl ElectionMethod for Plurality {
+    fn id(&self) -> &'static str {
+        "plurality"
+    }
+
+    fn name(&self) -> &'static str {
+        "Plurality (first past the post)"
+    }
+
+    fn ballot_type(&self) -> BallotType {
+        BallotType::Single
+    }
+
+    fn cast_ballot(&self, voter: &Voter, ballot: BallotMut) {
+        vote_plurality(voter, ballot.into_single());
+    }
+
+    fn tally(&self, ballots: &Ballots) -> Vec<f32> {
+        ballots.sum_votes(|votes, ballot: Single<&[u8]>, p| ballot.count_choice(votes, p))
+    }
+
+    fn select_winners(&self, ballots: &Ballots, num_votes: &[f32]) -> Winners {
+        ballots.declare_winners(num_votes, Goal::MostVotes).into()
+    }
+}
+
+/// Every voter approves of all candidates within `radius` of themselves.
+pub struct Approval {
+    pub radius: f32,
+}
+
+impl Default for Approval {
+    fn default() -> Approval {
+        Approval { radius: 1.0 }
+    }
+}
+
+impl ElectionMethod for Approval {
+    fn id(&self) -> &'static str {
+        "approval"
+    }
+
+    fn name(&self) -> &'static str {
+        "Approval"
+    }
+
+    fn ballot_type(&self) -> BallotType {
+        BallotType::Approval
+    }
+
+    fn parameters(&self) -> Vec<Parameter> {
+        vec![Parameter {
+            id: "radius",
+            name: "Approval radius",
+            value: self.radius,
+        }]
+    }
+
+    fn set_parameter(&mut self, id: &str, value: f32) -> Result<(), ElectionError> {
+        match id {
+            "radius" if value.is_finite() && value >= 0.0 => self.radius = value,
+            "radius" => {
+                return Err(ElectionError::InvalidParameter {
+                    id: "radius",
+                    value,
+                })
+            }
+            _ => {
+                return Err(ElectionError::UnknownParameter {
+                    method: self.id(),
+                    parameter: id.to_string(),
+                    value,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    fn cast_ballot(&self, voter: &Voter, ballot: BallotMut) {
+        vote_close(voter, self.radius, ballot.into_approval());
+    }
+
+    fn tally(&self, ballots: &Ballots) -> Vec<f32> {
+        ballots
+            .sum_votes(|votes, ballot: ApprovalBallot<&[u8]>, p| ballot.count_approvals(votes, p))
+    }
+
+    fn select_winners(&self, ballots: &Ballots, num_votes: &[f32]) -> Winners {
+        ballots.declare_winners(num_votes, Goal::MostVotes).into()
+    }
+}
+
+/// Voters rank the candidates, the candidate with the most Borda points wins.
+pub struct Borda;
+
+impl ElectionMethod for Borda {
+    fn id(&self) -> &'static str {
+        "borda"
+    }
+
+    fn name(&self) -> &'static str {
+        "Borda"
+    }
+
+    fn ballot_type(&self) -> BallotType {
+        BallotType::Ranking
+    }
+
+    fn cast_ballot(&self, voter: &Voter, ballot: BallotMut) {
+        vote_rank(voter, ballot.into_ranking());
+    }
+
+    fn tally(&self, ballots: &Ballots) -> Vec<f32> {
+        borda_count(ballots)
+    }
+
+    fn select_winners(&self, ballots: &Ballots, num_votes: &[f32]) -> Winners {
+        ballots.declare_winners(num_votes, Goal::MostVotes).into()
+    }
+}
+
+/// Sums up the Borda points of each candidate: a candidate gets one point for every rank below
+/// its own, so the favourite of n candidates gets n - 1 points and unranked candidates none.
+///
+/// Truncated ballots give the candidates they rank as many points as a full ballot would, so
+/// leaving a candidate off a ballot only takes points away from that candidate.
+pub(crate) fn borda_count(ballots: &Ballots) -> Vec<f32> {
+    ballots.sum_votes(|votes, ballot: Ranking<&[u8]>, p| ballot.count_borda(votes, p))
+}
+
+/// Instant runoff: the candidate with the fewest first preferences is eliminated until one
+/// candidate has a majority of the ballots that aren't exhausted yet.
+pub struct Hare;
+
+impl ElectionMethod for Hare {
+    fn id(&self) -> &'static str {
+        "hare"
+    }
+
+    fn name(&self) -> &'static str {
+        "Hare (instant runoff)"
+    }
+
+    fn ballot_type(&self) -> BallotType {
+        BallotType::Ranking
+    }
+
+    fn cast_ballot(&self, voter: &Voter, ballot: BallotMut) {
+        vote_rank(voter, ballot.into_ranking());
+    }
+
+    fn tally(&self, ballots: &Ballots) -> Vec<f32> {
+        ballots.sum_votes(|votes, ballot: Ranking<&[u8]>, p| ballot.count_top_choices(votes, 0, p))
+    }
+
+    fn select_winners(&self, ballots: &Ballots, num_votes: &[f32]) -> Winners {
+        let num_candidates = ballots.num_candidates();
+        let run_offs = run_off_all(ballots, num_votes);
+
+        let num_points = run_offs.len();
+        let mut winners = Vec::with_capacity(num_points);
+        let mut num_rounds = Vec::with_capacity(num_points);
+        let mut round_eliminated = Vec::with_capacity(num_points * num_candidates);
+        let mut round_votes = Vec::with_capacity(num_points * num_candidates.pow(2));
+        for run_off in run_offs {
+            winners.push(run_off.outcome.unwrap());
+            num_rounds.push(run_off.num_rounds);
+            round_eliminated.extend(run_off.round_eliminated);
+            round_votes.extend(run_off.round_votes);
+        }
+
+        let max_rounds = num_rounds.iter().cloned().max().unwrap_or(1);
+        let later_rounds = (1..max_rounds)
+            .map(|round| {
+                let mut totals = vec![0f32; num_points * num_candidates];
+                for (point, &point_rounds) in num_rounds.iter().enumerate() {
+                    let i = point * num_candidates;
+                    let votes = (i + round.min(point_rounds - 1)) * num_candidates;
+                    totals[i..i + num_candidates]
+                        .copy_from_slice(&round_votes[votes..votes + num_candidates]);
+                }
+                totals
+            })
+            .collect();
+        let eliminated = num_rounds
+            .iter()
+            .enumerate()
+            .map(|(point, &rounds)| round_eliminated[point * num_candidates + rounds - 1])
+            .collect();
+
+        Winners {
+            outcomes: winners,
+            later_rounds,
+            num_rounds,
+            eliminated,
+        }
+    }
+
+    fn explain(&self, ballots: &Ballots, num_votes: &[f32]) -> Explanation {
+        let num_candidates = ballots.num_candidates();
+        let run_off = run_off_all(ballots, num_votes).swap_remove(0);
+
+        let round_eliminated = &run_off.round_eliminated[..run_off.num_rounds];
+        let totals: Vec<&[f32]> = run_off
+            .round_votes
+            .chunks(num_candidates)
+            .take(run_off.num_rounds)
+            .collect();
+        let eliminated: Vec<Option<usize>> = round_eliminated
+            .windows(2)
+            .map(|rounds| Some((rounds[1] & !rounds[0]).trailing_zeros() as usize))
+            .collect();
+        Explanation::from_rounds(run_off.outcome.unwrap(), &totals, &eliminated)
+    }
+}
+
+/// Runs off every point of the map.
+///
+/// Unless the ballots were tallied by type, each round needs the vote totals of the whole map for
+/// the candidates eliminated so far. Points run off until they reach a set of eliminated
+/// candidates that wasn't counted yet, then each of these sets is counted once, and the points
+/// carry on.
+fn run_off_all(ballots: &Ballots, num_votes: &[f32]) -> Vec<RunOff> {
+    let num_candidates = ballots.num_candidates();
+    let mut votes_with_eliminated_candidates = HashMap::new();
+    votes_with_eliminated_candidates.insert(0, num_votes.to_vec());
+    let num_points = ballots.size().pow(2) as usize;
+    let mut run_offs: Vec<RunOff> = (0..num_points)
+        .map(|_| RunOff::new(num_candidates))
+        .collect();
+    loop {
+        parallel::for_each_chunk(&mut run_offs, 1, |point, run_off| {
+            run_off[0].resume(ballots, &votes_with_eliminated_candidates, point)
+        });
+        let uncounted: BTreeSet<u64> = run_offs
+            .iter()
+            .filter(|run_off| run_off.outcome.is_none())
+            .map(|run_off| run_off.eliminated)
+            .collect();
+        if uncounted.is_empty() {
+            return run_offs;
+        }
+        for eliminated in uncounted {
+            let num_votes = ballots.sum_votes(|votes, ballot: Ranking<&[u8]>, p| {
+                ballot.count_top_choices(votes, eliminated, p)
+            });
+            votes_with_eliminated_candidates.insert(eliminated, num_votes);
+        }
+    }
+}
+
+/// The rounds of the instant runoff at a single point, as far as they went.
+struct RunOff {
+    /// The winner, once the last round was counted.
+    outcome: Option<Outcome>,
+    num_rounds: usize,
+    /// The candidates eliminated before the next round.
+    eliminated: u64,
+    tied: bool,
+    /// The set of candidates eliminated before each round.
+    round_eliminated: Vec<u64>,
+    /// The vote totals of each round, `num_candidates` values per round.
+    round_votes: Vec<f32>,
+}
+
+impl RunOff {
+    fn new(num_candidates: usize) -> RunOff {
+        RunOff {
+            outcome: None,
+            num_rounds: 0,
+            eliminated: 0,
+            tied: false,
+            round_eliminated: vec![0; num_candidates],
+            round_votes: vec![0.0; num_candidates.pow(2)],
+        }
+    }
+
+    /// Eliminates candidates at `point` until one of them has a majority.
+    ///
+    /// Ballots tallied by type are counted at `point` alone. Otherwise, the vote totals of each
+    /// round come from `votes_with_eliminated_candidates`, the totals of the whole map for each
+    /// set of eliminated candidates. Stops early, without an outcome, if the current set isn't
+    /// there.
+    fn resume(
+        &mut self,
+        ballots: &Ballots,
+        votes_with_eliminated_candidates: &HashMap<u64, Vec<f32>>,
+        point: usize,
+    ) {
+        let num_candidates = ballots.num_candidates();
+        let vote_i = point * num_candidates;
+        while self.outcome.is_none() {
+            let round = self.num_rounds;
+            let eliminated = self.eliminated;
+            let (earlier, later) = self.round_votes.split_at_mut(round * num_candidates);
+            let votes = &mut later[..num_candidates];
+            let count = |votes: &mut [f32], ballot: Ranking<&[u8]>, p| {
+                ballot.count_top_choices(votes, eliminated, p)
+            };
+            if let Some(num_votes) = votes_with_eliminated_candidates.get(&eliminated) {
+                votes.copy_from_slice(&num_votes[vote_i..vote_i + num_candidates]);
+            } else if let Some(point_votes) = ballots.sum_votes_at(point, count) {
+                votes.copy_from_slice(&point_votes);
+            } else {
+                return;
+            }
+            let votes = &*votes;
+            let previous_votes = round
+                .checked_sub(1)
+                .map(|previous| &earlier[previous * num_candidates..]);
+            self.round_eliminated[round] = eliminated;
+            self.num_rounds += 1;
+
+            // Check if we have a winner.
+            let leader = ballots.pick(
+                Goal::MostVotes,
+                point,
+                votes,
+                eliminated,
+                round,
+                previous_votes,
+            );
+            let vote_sum: f32 = votes.iter().sum();
+            if votes[leader.winner] >= 0.5 * vote_sum || round + 1 == num_candidates {
+                // If one candidate has more than half the ballots, that candidate wins.
+                self.outcome = Some(Outcome {
+                    winner: leader.winner,
+                    tied: self.tied || leader.tied,
+                });
+                return;
+            }
+
+            // Otherwise, the candidate with the fewest ballots is eliminated and we vote again.
+            let loser = ballots.pick(
+                Goal::FewestVotes,
+                point,
+                votes,
+                eliminated,
+                round,
+                previous_votes,
+            );
+            self.tied |= loser.tied;
+            self.eliminated |= 1 << loser.winner;
+        }
+    }
+}
+
+/// Condorcet completion by minimax: the candidate whose worst pairwise contest is the closest to
+/// a win wins. This is always the Condorcet winner, if there is one.
+pub struct Minimax;
+
+impl ElectionMethod for Minimax {
+    fn id(&self) -> &'static str {
+        "minimax"
+    }
+
+    fn name(&self) -> &'static str {
+        "Minimax (Condorcet)"
+    }
+
+    fn ballot_type(&self) -> BallotType {
+        BallotType::Ranking
+    }
+
+    fn cast_ballot(&self, voter: &Voter, ballot: BallotMut) {
+        vote_rank(voter, ballot.into_ranking());
+    }
+
+    /// The vote total of a candidate is its lowest share of support in any pairwise contest.
+    fn tally(&self, ballots: &Ballots) -> Vec<f32> {
+        let num_candidates = ballots.num_candidates();
+        pairwise_support(ballots)
+            .chunks(num_candidates * num_candidates)
+            .flat_map(|matrix| {
+                matrix.chunks(num_candidates).enumerate().map(|(a, row)| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|&(b, _)| b != a)
+                        .map(|(_, &support)| support)
+                        .fold(1.0, f32::min)
+                })
+            })
+            .collect()
+    }
+
+    fn select_winners(&self, ballots: &Ballots, num_votes: &[f32]) -> Winners {
+        ballots.declare_winners(num_votes, Goal::MostVotes).into()
+    }
+
+    fn explain(&self, ballots: &Ballots, num_votes: &[f32]) -> Explanation {
+        let winners = self.select_winners(ballots, num_votes);
+        let mut explanation = Explanation::from_rounds(winners.outcomes[0], &[num_votes], &[]);
+        let num_candidates = ballots.num_candidates();
+        explanation.pairwise = Some(
+            pairwise_support(ballots)
+                .chunks(num_candidates)
+                .map(|row| row.to_vec())
+                .collect(),
+        );
+        explanation
+    }
+}
+
+/// The pairwise matrix at every point: the share of voters with a preference between candidates
+/// `a` and `b` who prefer `a`, at index `(point * num_candidates + a) * num_candidates + b`.
+///
+/// Candidates that no voter tells apart have a support of 0.5 against each other, and 0 against
+/// themselves.
+pub(crate) fn pairwise_support(ballots: &Ballots) -> Vec<f32> {
+    let num_candidates = ballots.num_candidates();
+    let num_points = ballots.size().pow(2) as usize;
+
+    // Number of voters preferring `a` over every other candidate, one pass per candidate.
+    let preferred: Vec<Vec<f32>> = (0..num_candidates)
+        .map(|a| {
+            ballots.sum_votes(|votes, ballot: Ranking<&[u8]>, p| ballot.count_below(votes, a, p))
+        })
+        .collect();
+
+    let mut support = vec![0f32; num_points * num_candidates * num_candidates];
+    for point in 0..num_points {
+        let i = point * num_candidates;
+        for a in 0..num_candidates {
+            for b in 0..num_candidates {
+                if a == b {
+                    continue;
+                }
+                let for_a = preferred[a][i + b];
+                let for_b = preferred[b][i + a];
+                support[(i + a) * num_candidates + b] = if for_a + for_b > 0.0 {
+                    for_a / (for_a + for_b)
+                } else {
+                    0.5
+                };
+            }
+        }
+    }
+    support
+}
+
+/// The list of election methods available to the simulator.
+///
+/// Starts out with all methods built into this crate; other crates can `register` their own.
+pub struct MethodRegistry {
+    methods: Vec<Box<dyn ElectionMethod>>,
+}
+
+impl MethodRegistry {
+    /// Creates a registry without any methods.
+    pub fn empty() -> MethodRegistry {
+        MethodRegistry { methods: vec![] }
+    }
+
+    /// Adds a method to the registry, replacing any existing method with the same id.
+    pub fn register(&mut self, method: Box<dyn ElectionMethod>) {
+        self.methods.retain(|m| m.id() != method.id());
+        self.methods.push(method);
+    }
+
+    pub fn get(&self, id: &str) -> Result<&dyn ElectionMethod, ElectionError> {
+        self.methods
+            .iter()
+            .find(|m| m.id() == id)
+            .map(|m| &**m)
+            .ok_or_else(|| ElectionError::UnknownMethod(id.to_string()))
+    }
+
+    /// The method with this id, with its parameters set to `values`, in the order of
+    /// `ElectionMethod::parameters`.
+    pub fn configure(
+        &mut self,
+        id: &str,
+        values: &[f32],
+    ) -> Result<&dyn ElectionMethod, ElectionError> {
+        let method = self
+            .methods
+            .iter_mut()
+            .find(|m| m.id() == id)
+            .ok_or_else(|| ElectionError::UnknownMethod(id.to_string()))?;
+        let parameters = method.parameters();
+        if values.len() != parameters.len() {
+            return Err(ElectionError::ParameterCount {
+                method: method.id(),
+                count: values.len(),
+                expected: parameters.len(),
+            });
+        }
+        for (parameter, &value) in parameters.iter().zip(values) {
+            method.set_parameter(parameter.id, value)?;
+        }
+        Ok(&**method)
+    }
+
+    /// The method at `index` in the order of `iter`.
+    pub fn get_index(&self, index: usize) -> Result<&dyn ElectionMethod, ElectionError> {
+        self.methods
+            .get(index)
+            .map(|m| &**m)
+            .ok_or(ElectionError::MethodIndexOutOfRange {
+                index,
+                count: self.methods.len(),
+            })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn ElectionMethod> {
+        self.methods.iter().map(|m| &**m)
+    }
+
+    pub fn len(&self) -> usize {
+        self.methods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.methods.is_empty()
+    }
+}
+
+impl Default for MethodRegistry {
+    fn default() -> MethodRegistry {
+        let mut registry = MethodRegistry::empty();
+        registry.register(Box::new(Plurality));
+        registry.register(Box::new(Approval::default()));
+        registry.register(Box::new(Borda));
+        registry.register(Box::new(Hare));
+        registry.register(Box::new(Minimax));
+        registry
+    }
+}
@@ -0,0 +1,199 @@
+//! Distances between voters and candidates.
+
+use std::str::FromStr;
+
+use crate::Point;
+
+/// How voters measure their distance to candidates.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Metric {
+    #[default]
+    Euclidean,
+    /// Sum of the distances along each axis (L1).
+    CityBlock,
+    /// Largest of the distances along each axis (L∞).
+    Chebyshev,
+    /// Minkowski distance of order `p`, at least 1.
+    Minkowski { p: f32 },
+    /// Euclidean distance where each axis counts `x` and `y` times as much, for voters who care
+    /// more about one issue than the other.
+    Salience { x: f32, y: f32 },
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    /// Parses `euclidean`, `cityblock`, `chebyshev`, `minkowski:<p>` or `salience:<x>,<y>`.
+    fn from_str(s: &str) -> Result<Metric, String> {
+        match s {
+            "euclidean" => return Ok(Metric::Euclidean),
+            "cityblock" => return Ok(Metric::CityBlock),
+            "chebyshev" => return Ok(Metric::Chebyshev),
+            _ => {}
+        }
+        if let Some(Ok(p)) = s.strip_prefix("minkowski:").map(str::parse) {
+            return Ok(Metric::Minkowski { p });
+        }
+        let salience = s
+            .strip_prefix("salience:")
+            .and_then(|xy| xy.split_once(','));
+        if let Some((Ok(x), Ok(y))) = salience.map(|(x, y)| (x.parse(), y.parse())) {
+            return Ok(Metric::Salience { x, y });
+        }
+        Err(s.to_string())
+    }
+}
+
+impl Metric {
+    /// A measure of the distance between `a` and `b` that is cheaper to compute than the distance
+    /// itself, like the square of the Euclidean distance. Measures compare like the distances
+    /// they come from.
+    pub fn measure(&self, a: &Point, b: &Point) -> f32 {
+        let (dx, dy) = ((a.x - b.x).abs(), (a.y - b.y).abs());
+        match *self {
+            Metric::Euclidean => a.l2_square(b),
+            Metric::CityBlock => dx + dy,
+            Metric::Chebyshev => dx.max(dy),
+            Metric::Minkowski { p } => dx.powf(p) + dy.powf(p),
+            Metric::Salience { x, y } => x * dx.powi(2) + y * dy.powi(2),
+        }
+    }
+
+    /// Extends the measure of a distance in the plane of the map by the distance `delta` along a
+    /// hidden issue. Hidden issues have a salience of 1.
+    pub fn extend(&self, measure: f32, delta: f32) -> f32 {
+        match *self {
+            Metric::Euclidean | Metric::Salience { .. } => measure + delta.powi(2),
+            Metric::CityBlock => measure + delta.abs(),
+            Metric::Chebyshev => measure.max(delta.abs()),
+            Metric::Minkowski { p } => measure + delta.abs().powf(p),
+        }
+    }
+
+    /// The measure of a `distance`.
+    pub fn measure_of(&self, distance: f32) -> f32 {
+        match *self {
+            Metric::Euclidean | Metric::Salience { .. } => distance.powi(2),
+            Metric::CityBlock | Metric::Chebyshev => distance,
+            Metric::Minkowski { p } => distance.powf(p),
+        }
+    }
+
+    /// The distance between `a` and `b` in the plane of the map.
+    pub fn distance(&self, a: &Point, b: &Point) -> f32 {
+        self.distance_of(self.measure(a, b))
+    }
+
+    /// The distance with this `measure`.
+    pub fn distance_of(&self, measure: f32) -> f32 {
+        match *self {
+            Metric::Euclidean | Metric::Salience { .. } => measure.sqrt(),
+            Metric::CityBlock | Metric::Chebyshev => measure,
+            Metric::Minkowski { p } => measure.powf(1.0 / p),
+        }
+    }
+}
+
+/// Qualities of a candidate other than their position, such as charisma or competence.
+///
+/// Voters judge a candidate by their distance to them, divided by `quality`, less `bonus`. With a
+/// narrow electorate, plurality regions are additively or multiplicatively weighted Voronoi cells.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Valence {
+    /// Subtracted from the distance to the candidate, in map units.
+    pub bonus: f32,
+    /// Divides the distance to the candidate, 1 for an ordinary candidate.
+    pub quality: f32,
+}
+
+impl Default for Valence {
+    fn default() -> Valence {
+        Valence {
+            bonus: 0.0,
+            quality: 1.0,
+        }
+    }
+}
+
+impl Valence {
+    /// A valence that is subtracted from the distance to the candidate.
+    pub const fn additive(bonus: f32) -> Valence {
+        Valence {
+            bonus,
+            quality: 1.0,
+        }
+    }
+
+    /// A valence that divides the distance to the candidate.
+    pub const fn multiplicative(quality: f32) -> Valence {
+        Valence {
+            bonus: 0.0,
+            quality,
+        }
+    }
+
+    /// How far a candidate at `distance` seems to voters.
+    pub fn apply(&self, distance: f32) -> f32 {
+        distance / self.quality - self.bonus
+    }
+}
+
+/// A voter at a single location, filling in their ballot.
+pub struct Voter<'a> {
+    pub position: Point,
+    /// Position along the hidden issues of the slice shown on the map, if any.
+    pub hidden: &'a [f32],
+    pub metric: Metric,
+    /// The measure of the distance to each candidate.
+    measures: &'a [f32],
+    /// Whether `measures` are distances adjusted for valence, rather than measures.
+    adjusted: bool,
+}
+
+impl<'a> Voter<'a> {
+    pub(crate) fn new(
+        position: Point,
+        hidden: &'a [f32],
+        metric: Metric,
+        measures: &'a [f32],
+        adjusted: bool,
+    ) -> Voter<'a> {
+        Voter {
+            position,
+            hidden,
+            metric,
+            measures,
+            adjusted,
+        }
+    }
+
+    pub fn num_candidates(&self) -> usize {
+        self.measures.len()
+    }
+
+    /// The measure of the distance to the candidate, see `Metric::measure`. Voters prefer
+    /// candidates with a lower measure.
+    ///
+    /// When candidates have a valence, this is the distance adjusted for the valence instead.
+    pub fn measure(&self, candidate: usize) -> f32 {
+        self.measures[candidate]
+    }
+
+    /// The distance to the candidate, taking their valence into account.
+    pub fn distance(&self, candidate: usize) -> f32 {
+        if self.adjusted {
+            self.measures[candidate]
+        } else {
+            self.metric.distance_of(self.measures[candidate])
+        }
+    }
+
+    /// Whether the candidate is closer than `distance`, taking their valence into account.
+    pub fn is_within(&self, candidate: usize, distance: f32) -> bool {
+        if self.adjusted {
+            self.measures[candidate] < distance
+        } else {
+            self.measures[candidate] < self.metric.measure_of(distance)
+        }
+    }
+}
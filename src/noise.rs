@@ -0,0 +1,87 @@
+//! Voters who don't always prefer the closest candidate.
+
+use std::str::FromStr;
+
+use crate::tie::splitmix64;
+
+/// How voters turn their distances to candidates into ballots.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum VoterModel {
+    /// Voters always prefer closer candidates.
+    #[default]
+    Deterministic,
+    /// Random utility model: voters rank candidates by their distance less `temperature` times
+    /// Gumbel noise. First choices then follow a multinomial logit, and whole rankings a
+    /// Plackett–Luce distribution, with weights `exp(-distance / temperature)`.
+    ///
+    /// This is a Monte Carlo approximation: every voter casts `draws` ballots with independent
+    /// noise, and the electorate around each point averages them. The noise depends on where
+    /// voters are, on a lattice of `NOISE_LATTICE` cells across the map, not on the pixel, so
+    /// maps of any size up to that sample the same noise at the same place.
+    Logit { temperature: f32, draws: usize },
+}
+
+/// Number of cells across each axis of the map that voters draw their noise on.
+pub const NOISE_LATTICE: i32 = 4096;
+
+impl FromStr for VoterModel {
+    type Err = String;
+
+    /// Parses `deterministic`, or `logit:<temperature>` with 16 draws or
+    /// `logit:<temperature>,<draws>`.
+    fn from_str(s: &str) -> Result<VoterModel, String> {
+        if s == "deterministic" {
+            return Ok(VoterModel::Deterministic);
+        }
+        let logit = s
+            .strip_prefix("logit:")
+            .map(|args| match args.split_once(',') {
+                Some((temperature, draws)) => (temperature.parse(), draws.parse()),
+                None => (args.parse(), Ok(16)),
+            });
+        match logit {
+            Some((Ok(temperature), Ok(draws))) => Ok(VoterModel::Logit { temperature, draws }),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+impl VoterModel {
+    /// Number of ballots cast at each point.
+    pub fn draws(&self) -> usize {
+        match *self {
+            VoterModel::Deterministic => 1,
+            VoterModel::Logit { draws, .. } => draws,
+        }
+    }
+
+    /// Whether voters at the same point may cast different ballots.
+    pub fn is_random(&self) -> bool {
+        *self != VoterModel::Deterministic
+    }
+
+    /// How much further than its distance the candidate seems to the voter at pixel `(x, y)` of
+    /// a `map_size` x `map_size` map, in their `draw`th ballot. Voters in the same cell of the
+    /// noise lattice always get the same noise.
+    pub(crate) fn noise(
+        &self,
+        (x, y): (i32, i32),
+        map_size: i32,
+        draw: usize,
+        candidate: usize,
+    ) -> f32 {
+        match *self {
+            VoterModel::Deterministic => 0.0,
+            VoterModel::Logit { temperature, .. } => {
+                let cell = |p: i32| {
+                    (p as i64 * NOISE_LATTICE as i64).div_euclid(map_size as i64) as u32 as u64
+                };
+                let cell = splitmix64((cell(x) << 32) | cell(y));
+                let hash = splitmix64(splitmix64(cell ^ draw as u64) ^ candidate as u64);
+                // Uniform in (0, 1), then Gumbel distributed.
+                let u = ((hash >> 40) as f32 + 0.5) / (1u64 << 24) as f32;
+                temperature * (-u.ln()).ln()
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Loops over independent parts of the map, which run on all threads with the `parallel`
+//! feature, and one after the other without it, as they must in wasm.
+//!
+//! Each part is computed the same way either way and the results are put back in order, so maps
+//! are bit for bit the same with and without the feature. The bounds don't depend on the feature
+//! either, so that code which builds without it also builds with it.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// `f` of every index of `0..len`, in order.
+pub(crate) fn map<R, F>(len: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(usize) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    let results = (0..len).into_par_iter().map(f).collect();
+    #[cfg(not(feature = "parallel"))]
+    let results = (0..len).map(f).collect();
+    results
+}
+
+/// `f` of every index of `0..len`, in order, with scratch state made by `init`, which may be
+/// reused for several indices.
+pub(crate) fn map_init<S, R, I, F>(len: usize, init: I, f: F) -> Vec<R>
+where
+    R: Send,
+    I: Fn() -> S + Sync + Send,
+    F: Fn(&mut S, usize) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    let results = (0..len).into_par_iter().map_init(init, f).collect();
+    #[cfg(not(feature = "parallel"))]
+    let results = {
+        let mut state = init();
+        (0..len).map(|i| f(&mut state, i)).collect()
+    };
+    results
+}
+
+/// Calls `f` with the index and contents of every `chunk_size` chunk of `data`.
+pub(crate) fn for_each_chunk<T, F>(data: &mut [T], chunk_size: usize, f: F)
+where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    data.par_chunks_mut(chunk_size)
+        .enumerate()
+        .for_each(|(i, chunk)| f(i, chunk));
+    #[cfg(not(feature = "parallel"))]
+    for (i, chunk) in data.chunks_mut(chunk_size).enumerate() {
+        f(i, chunk);
+    }
+}
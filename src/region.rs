@@ -0,0 +1,391 @@
+//! Exact regions of the plane where voters cast the same ranked ballot.
+
+use std::f64::consts::TAU;
+use std::str::FromStr;
+
+use crate::ballot::BallotMut;
+use crate::histogram::{BallotTypes, Histogram};
+use crate::{Covariance, ElectionMethod, Metric, Point, Settings, Voter};
+
+/// How the ballots of the electorate around each point are added up.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Integration {
+    /// Voters sit on the pixels of a grid around each point, up to `num_sigma` standard
+    /// deviations from the center of their cluster.
+    #[default]
+    Grid,
+    /// Sincere voters ranking candidates by Euclidean distance cast the same ballot anywhere in a
+    /// convex polygon bounded by the bisectors of the candidates: a cell of the higher-order
+    /// Voronoi diagram. The mass of each Gaussian cluster is integrated over these polygons, so
+    /// vote shares don't depend on the size of the map, nor on `num_sigma`.
+    ///
+    /// The number of cells grows with the fourth power of the number of candidates, unless
+    /// ballots only list the first few of them, so this is slower than the grid for many
+    /// candidates, and supports at most `MAX_EXACT_CANDIDATES` of them.
+    Exact,
+}
+
+impl FromStr for Integration {
+    type Err = String;
+
+    /// Parses `grid` or `exact`.
+    fn from_str(s: &str) -> Result<Integration, String> {
+        match s {
+            "grid" => Ok(Integration::Grid),
+            "exact" => Ok(Integration::Exact),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+/// Clusters are integrated up to this many standard deviations from their center, which leaves
+/// out less than 1e-13 of their voters.
+const REACH: f64 = 8.0;
+
+/// A convex polygon, its vertices in counter-clockwise order.
+type Polygon = Vec<[f64; 2]>;
+
+/// A region where voters cast the same ballot.
+struct Cell {
+    /// Number of the ballot among the distinct ballots.
+    ballot: u32,
+    polygon: Polygon,
+    /// Corners of the bounding box of the polygon.
+    min: [f64; 2],
+    max: [f64; 2],
+}
+
+/// The ballots cast around every point of the `size` x `size` window at `origin` of a
+/// `map_size` x `map_size` map, with the weights of a point adding up to 1.
+///
+/// The election must be sincere, with Euclidean distances, Gaussian clusters and no valence,
+/// hidden issue, random voter model, partial turnout or truncation by radius.
+pub(crate) fn histogram(
+    map_size: i32,
+    origin: (i32, i32),
+    size: i32,
+    candidates: &[Point],
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+) -> Histogram {
+    let num_candidates = candidates.len();
+    let electorate = &settings.electorate;
+    let total_weight = electorate.total_weight() as f64;
+    let to_map = |pixel: i32| pixel as f64 / map_size as f64;
+
+    // Every cell that some cluster around the window reaches is within this box.
+    let pad = electorate
+        .clusters
+        .iter()
+        .map(|cluster| {
+            let sigma = cluster.covariance.sigma_x.max(cluster.covariance.sigma_y);
+            let offset = cluster.offset.x.abs().max(cluster.offset.y.abs());
+            offset as f64 + 2.0 * REACH * sigma as f64
+        })
+        .fold(0.0, f64::max);
+    let bounds = |window: i32, coordinate: fn(&Point) -> f32| {
+        let (min, max) = candidates
+            .iter()
+            .map(|c| coordinate(c) as f64)
+            .fold((to_map(window), to_map(window + size)), |(min, max), c| {
+                (min.min(c), max.max(c))
+            });
+        (min - pad, max + pad)
+    };
+    let (min_x, max_x) = bounds(origin.0, |c| c.x);
+    let (min_y, max_y) = bounds(origin.1, |c| c.y);
+    let bounding_box = vec![
+        [min_x, min_y],
+        [max_x, min_y],
+        [max_x, max_y],
+        [min_x, max_y],
+    ];
+
+    let points: Vec<[f64; 2]> = candidates
+        .iter()
+        .map(|c| [c.x as f64, c.y as f64])
+        .collect();
+    // Cast a ballot inside every cell, from its vertex centroid.
+    let mut measures = vec![0f32; num_candidates];
+    let mut cast = |polygon: &[[f64; 2]]| {
+        let n = polygon.len() as f64;
+        let at = Point::new(
+            (polygon.iter().map(|p| p[0]).sum::<f64>() / n) as f32,
+            (polygon.iter().map(|p| p[1]).sum::<f64>() / n) as f32,
+        );
+        for (measure, candidate) in measures.iter_mut().zip(candidates) {
+            *measure = Metric::Euclidean.measure(&at, candidate);
+        }
+        let voter = Voter::new(at, &[], Metric::Euclidean, &measures, false);
+        let mut ballot = vec![0u8; num_candidates];
+        method.cast_ballot(&voter, BallotMut::new(method.ballot_type(), &mut ballot));
+        settings
+            .truncation
+            .apply(&voter, BallotMut::new(method.ballot_type(), &mut ballot));
+        ballot
+    };
+    let remaining: Vec<usize> = (0..num_candidates).collect();
+    let polygons = split(&points, bounding_box, &remaining, &mut cast);
+
+    // Number the distinct ballots.
+    let mut types = BallotTypes::new(num_candidates);
+    let cells: Vec<Cell> = polygons
+        .into_iter()
+        .map(|(ballot, polygon)| {
+            let min = polygon
+                .iter()
+                .fold([f64::MAX; 2], |min, p| [min[0].min(p[0]), min[1].min(p[1])]);
+            let max = polygon
+                .iter()
+                .fold([f64::MIN; 2], |max, p| [max[0].max(p[0]), max[1].max(p[1])]);
+            Cell {
+                ballot: types.number(&ballot),
+                polygon,
+                min,
+                max,
+            }
+        })
+        .collect();
+
+    let mut masses = vec![0f64; types.len()];
+    let mut histogram = Histogram::new(types);
+    for x in 0..size {
+        for y in 0..size {
+            let at = [to_map(origin.0 + x), to_map(origin.1 + y)];
+            masses.iter_mut().for_each(|mass| *mass = 0.0);
+            for cluster in electorate.clusters.iter().filter(|c| c.weight > 0.0) {
+                let weight = cluster.weight as f64 / total_weight;
+                let center = [
+                    at[0] + cluster.offset.x as f64,
+                    at[1] + cluster.offset.y as f64,
+                ];
+                let gaussian = Gaussian::new(center, cluster.covariance);
+                for cell in cells.iter().filter(|cell| gaussian.reaches(cell)) {
+                    masses[cell.ballot as usize] += weight * gaussian.mass(&cell.polygon);
+                }
+            }
+            let counts = masses.iter().enumerate();
+            histogram.push(counts.map(|(ballot, &mass)| (ballot as u32, mass as f32)));
+        }
+    }
+    histogram
+}
+
+/// Splits `polygon` into the cells where voters rank the `remaining` candidates in the same order,
+/// and casts a ballot in each of them with `cast`.
+///
+/// The candidate ranked first in a cell is closer than all other remaining candidates, so it
+/// lies in the intersection of the half-planes bounded by the bisectors with each of them.
+/// Candidates at the same position are ordered by index. If voters cast the same ballot in all
+/// the cells, as they do when ballots only list the first few candidates, `polygon` is kept
+/// whole, which saves integrating over the edges between the cells.
+fn split(
+    points: &[[f64; 2]],
+    polygon: Polygon,
+    remaining: &[usize],
+    cast: &mut dyn FnMut(&[[f64; 2]]) -> Vec<u8>,
+) -> Vec<(Vec<u8>, Polygon)> {
+    if remaining.len() <= 1 {
+        return vec![(cast(&polygon), polygon)];
+    }
+    let mut cells = vec![];
+    for &first in remaining {
+        let mut cell = polygon.clone();
+        for &other in remaining.iter().filter(|&&other| other != first) {
+            let (a, b) = (points[first], points[other]);
+            // Points p closer to `a` than to `b` have 2 p.(b - a) < |b|^2 - |a|^2.
+            let normal = [2.0 * (b[0] - a[0]), 2.0 * (b[1] - a[1])];
+            if normal == [0.0, 0.0] {
+                if other < first {
+                    cell.clear();
+                }
+            } else {
+                let bound = (b[0].powi(2) + b[1].powi(2)) - (a[0].powi(2) + a[1].powi(2));
+                cell = clip(&cell, normal, bound);
+            }
+            if cell.len() < 3 {
+                break;
+            }
+        }
+        if area(&cell) > 0.0 {
+            let others: Vec<usize> = remaining.iter().filter(|&&c| c != first).copied().collect();
+            cells.extend(split(points, cell, &others, cast));
+        }
+    }
+    if cells.len() > 1 && cells.iter().all(|(ballot, _)| *ballot == cells[0].0) {
+        let (ballot, _) = cells.swap_remove(0);
+        return vec![(ballot, polygon)];
+    }
+    cells
+}
+
+/// The part of a convex polygon where `normal . p <= bound`.
+fn clip(polygon: &[[f64; 2]], normal: [f64; 2], bound: f64) -> Polygon {
+    let side = |p: [f64; 2]| normal[0] * p[0] + normal[1] * p[1] - bound;
+    let mut clipped = vec![];
+    for (i, &p) in polygon.iter().enumerate() {
+        let q = polygon[(i + 1) % polygon.len()];
+        let (sp, sq) = (side(p), side(q));
+        if sp <= 0.0 {
+            clipped.push(p);
+        }
+        if (sp < 0.0 && sq > 0.0) || (sp > 0.0 && sq < 0.0) {
+            let t = sp / (sp - sq);
+            clipped.push([p[0] + t * (q[0] - p[0]), p[1] + t * (q[1] - p[1])]);
+        }
+    }
+    clipped
+}
+
+/// Area of a polygon, positive if its vertices are in counter-clockwise order.
+fn area(polygon: &[[f64; 2]]) -> f64 {
+    let twice: f64 = (0..polygon.len())
+        .map(|i| {
+            let (p, q) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            p[0] * q[1] - p[1] * q[0]
+        })
+        .sum();
+    twice / 2.0
+}
+
+/// A cluster of voters with a Gaussian distribution.
+struct Gaussian {
+    center: [f64; 2],
+    /// The covariance is L * L^T, with L = [[l11, 0], [l21, l22]].
+    l11: f64,
+    l21: f64,
+    l22: f64,
+    /// Voters further than this from the center along any direction are left out.
+    reach: f64,
+}
+
+impl Gaussian {
+    fn new(center: [f64; 2], covariance: Covariance) -> Gaussian {
+        let (sigma_x, sigma_y) = (covariance.sigma_x as f64, covariance.sigma_y as f64);
+        let correlation = covariance.correlation as f64;
+        Gaussian {
+            center,
+            l11: sigma_x,
+            l21: correlation * sigma_y,
+            l22: sigma_y * (1.0 - correlation.powi(2)).sqrt(),
+            reach: REACH * (sigma_x.powi(2) + sigma_y.powi(2)).sqrt(),
+        }
+    }
+
+    /// Whether the cluster has voters in the bounding box of the cell.
+    fn reaches(&self, cell: &Cell) -> bool {
+        let gap = |axis: usize| {
+            (cell.min[axis] - self.center[axis])
+                .max(self.center[axis] - cell.max[axis])
+                .max(0.0)
+        };
+        gap(0).powi(2) + gap(1).powi(2) <= self.reach.powi(2)
+    }
+
+    /// Maps `p` to standard deviations from the center, in independent directions.
+    fn standardize(&self, p: [f64; 2]) -> [f64; 2] {
+        let u = (p[0] - self.center[0]) / self.l11;
+        let v = (p[1] - self.center[1] - self.l21 * u) / self.l22;
+        [u, v]
+    }
+
+    /// Share of the voters inside a convex polygon with vertices in counter-clockwise order.
+    ///
+    /// Once standardized, the polygon is a sum of signed triangles between the center and each
+    /// edge, and the share of voters in each triangle is a one dimensional integral.
+    fn mass(&self, polygon: &[[f64; 2]]) -> f64 {
+        let mass: f64 = (0..polygon.len())
+            .map(|i| {
+                let p = self.standardize(polygon[i]);
+                let q = self.standardize(polygon[(i + 1) % polygon.len()]);
+                triangle_mass(p, q)
+            })
+            .sum();
+        mass.clamp(0.0, 1.0)
+    }
+}
+
+/// Share of a standard normal distribution in the triangle between the origin, `p` and `q`;
+/// negative if the triangle is clockwise.
+///
+/// In polar coordinates, the share within distance r along a direction is (1 - e^(-r^2 / 2)) /
+/// 2pi. Along the edge, r = h / cos(psi), where h is the distance from the origin to the line
+/// through the edge and psi the angle from the closest point of that line.
+fn triangle_mass(p: [f64; 2], q: [f64; 2]) -> f64 {
+    let edge = [q[0] - p[0], q[1] - p[1]];
+    let length_squared = edge[0].powi(2) + edge[1].powi(2);
+    let cross = p[0] * q[1] - p[1] * q[0];
+    if length_squared == 0.0 || cross == 0.0 {
+        return 0.0;
+    }
+    let h = cross.abs() / length_squared.sqrt();
+    // Unit vector towards the closest point of the line.
+    let t = -(p[0] * edge[0] + p[1] * edge[1]) / length_squared;
+    let normal = [(p[0] + t * edge[0]) / h, (p[1] + t * edge[1]) / h];
+    let angle = |v: [f64; 2]| (normal[0] * v[1] - normal[1] * v[0]).atan2(h);
+    let (from, to) = (angle(p), angle(q));
+    if h > REACH {
+        return (to - from) / TAU;
+    }
+    let share = |psi: f64| -(-h.powi(2) / (2.0 * psi.cos().powi(2))).exp_m1();
+    integrate(&share, from, to) / TAU
+}
+
+/// Integral of a smooth function from `a` to `b`, to within about 1e-10.
+///
+/// The integrand above drops sharply towards +-pi/2 for edges close to the origin, so the
+/// interval is bisected wherever the 7 and 15 point rules of Gauss-Kronrod disagree.
+fn integrate(f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+    adaptive(f, a, b, 1e-10, 30)
+}
+
+fn adaptive(f: &dyn Fn(f64) -> f64, a: f64, b: f64, tolerance: f64, depth: u32) -> f64 {
+    let (gauss, kronrod) = gauss_kronrod(f, a, b);
+    if depth == 0 || (kronrod - gauss).abs() <= tolerance {
+        return kronrod;
+    }
+    let middle = (a + b) / 2.0;
+    adaptive(f, a, middle, tolerance / 2.0, depth - 1)
+        + adaptive(f, middle, b, tolerance / 2.0, depth - 1)
+}
+
+/// Nodes of the 15 point Kronrod rule on [-1, 1] from the outermost to the center, with their
+/// weights in that rule and, for every other node, in the 7 point Gauss rule.
+const GAUSS_KRONROD: [(f64, f64, f64); 8] = [
+    (0.991_455_371_120_812_6, 0.022_935_322_010_529_22, 0.0),
+    (
+        0.949_107_912_342_758_5,
+        0.063_092_092_629_978_55,
+        0.129_484_966_168_869_7,
+    ),
+    (0.864_864_423_359_769_1, 0.104_790_010_322_250_18, 0.0),
+    (
+        0.741_531_185_599_394_4,
+        0.140_653_259_715_525_92,
+        0.279_705_391_489_276_7,
+    ),
+    (0.586_087_235_467_691_1, 0.169_004_726_639_267_9, 0.0),
+    (
+        0.405_845_151_377_397_2,
+        0.190_350_578_064_785_4,
+        0.381_830_050_505_118_9,
+    ),
+    (0.207_784_955_007_898_47, 0.204_432_940_075_298_9, 0.0),
+    (0.0, 0.209_482_141_084_727_83, 0.417_959_183_673_469_4),
+];
+
+/// The 7 point Gauss and 15 point Kronrod estimates of the integral of `f` from `a` to `b`.
+fn gauss_kronrod(f: &dyn Fn(f64) -> f64, a: f64, b: f64) -> (f64, f64) {
+    let (middle, half) = ((a + b) / 2.0, (b - a) / 2.0);
+    let (mut gauss, mut kronrod) = (0.0, 0.0);
+    for &(x, kronrod_weight, gauss_weight) in GAUSS_KRONROD.iter() {
+        let sum = if x == 0.0 {
+            f(middle)
+        } else {
+            f(middle - half * x) + f(middle + half * x)
+        };
+        gauss += gauss_weight * sum;
+        kronrod += kronrod_weight * sum;
+    }
+    (gauss * half, kronrod * half)
+}
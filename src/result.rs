@@ -0,0 +1,150 @@
+//! The full result of simulating an election on a map.
+
+use crate::{Electorate, Outcome};
+
+/// The winners picked by an election method, along with the vote totals of any later rounds.
+#[derive(Clone, Debug)]
+pub struct Winners {
+    /// The winner at each point.
+    pub outcomes: Vec<Outcome>,
+    /// Vote totals of every round after the first, laid out like `ElectionResult::rounds`.
+    pub later_rounds: Vec<Vec<f32>>,
+    /// Number of rounds it took to find the winner at each point.
+    pub num_rounds: Vec<usize>,
+    /// Bitmask of the candidates eliminated before the final round at each point.
+    pub eliminated: Vec<u64>,
+}
+
+impl From<Vec<Outcome>> for Winners {
+    /// Winners of a method with a single round.
+    fn from(outcomes: Vec<Outcome>) -> Winners {
+        let num_rounds = vec![1; outcomes.len()];
+        let eliminated = vec![0; outcomes.len()];
+        Winners {
+            outcomes,
+            later_rounds: vec![],
+            num_rounds,
+            eliminated,
+        }
+    }
+}
+
+/// The result of simulating an election at every point of a map.
+///
+/// # Layout
+///
+/// Per-point values are stored in column-major order: the point at pixel (x, y) has index
+/// `x * size + y`, and represents an electorate centered on `(x / size, y / size)`.
+///
+/// Per-candidate values add an innermost dimension: the value for candidate `c` at point `i` is
+/// at index `i * num_candidates + c`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElectionResult {
+    /// Width and height of the map, in pixels.
+    pub size: usize,
+    pub num_candidates: usize,
+    /// The voters around each point.
+    pub electorate: Electorate,
+    /// Id of the election method.
+    pub method: String,
+    /// The winner at each point; one value per point.
+    pub outcomes: Vec<Outcome>,
+    /// Vote share of each candidate in each round; one value per point and candidate.
+    ///
+    /// Shares at each point sum to 1, higher is better. Methods with a single round have exactly
+    /// one round. Points that were decided before the last round repeat their final round.
+    pub rounds: Vec<Vec<f32>>,
+    /// Number of rounds it took to find the winner at each point; one value per point.
+    pub num_rounds: Vec<usize>,
+    /// Share of the ballots that rank none of the candidates left in the final round; one value
+    /// per point. Only truncated ranked ballots run out, so this is 0 everywhere otherwise.
+    pub exhausted: Vec<f32>,
+    /// Share of the electorate around each point that turns out to vote; one value per point.
+    pub turnout: Vec<f32>,
+}
+
+impl ElectionResult {
+    pub(crate) fn new(
+        size: usize,
+        electorate: Electorate,
+        method: &str,
+        num_votes: Vec<f32>,
+        winners: Winners,
+        exhausted: Vec<f32>,
+        turnout: Vec<f32>,
+    ) -> ElectionResult {
+        let num_candidates = num_votes.len() / size.pow(2);
+        let rounds = std::iter::once(num_votes)
+            .chain(winners.later_rounds)
+            .map(|mut totals| {
+                for votes in totals.chunks_mut(num_candidates) {
+                    normalize(votes);
+                }
+                totals
+            })
+            .collect();
+        ElectionResult {
+            size,
+            num_candidates,
+            electorate,
+            method: method.to_string(),
+            outcomes: winners.outcomes,
+            rounds,
+            num_rounds: winners.num_rounds,
+            exhausted,
+            turnout,
+        }
+    }
+
+    /// Vote shares in the first round; one value per point and candidate.
+    pub fn shares(&self) -> &[f32] {
+        &self.rounds[0]
+    }
+
+    /// The winner at each point; one value per point.
+    pub fn winners(&self) -> impl Iterator<Item = usize> + '_ {
+        self.outcomes.iter().map(|o| o.winner)
+    }
+
+    /// The outcome at pixel (x, y).
+    pub fn outcome_at(&self, x: usize, y: usize) -> Outcome {
+        self.outcomes[x * self.size + y]
+    }
+
+    /// Margin of victory at each point; one value per point.
+    ///
+    /// The margin is the difference between the vote shares of the winner and the runner-up in the
+    /// final round, from 0 for a tie to 1 for a unanimous win.
+    pub fn margins(&self) -> Vec<f32> {
+        let last = self.rounds.last().unwrap();
+        self.outcomes
+            .iter()
+            .zip(last.chunks(self.num_candidates))
+            .map(|(outcome, shares)| {
+                let runner_up = shares
+                    .iter()
+                    .enumerate()
+                    .filter(|&(c, _)| c != outcome.winner)
+                    .map(|(_, &share)| share)
+                    .fold(0.0, f32::max);
+                (shares[outcome.winner] - runner_up).max(0.0)
+            })
+            .collect()
+    }
+
+    /// The first round vote shares of all candidates at pixel (x, y).
+    pub fn shares_at(&self, x: usize, y: usize) -> &[f32] {
+        let i = (x * self.size + y) * self.num_candidates;
+        &self.shares()[i..i + self.num_candidates]
+    }
+}
+
+/// Scales `votes` to sum to 1, unless nobody voted at all.
+fn normalize(votes: &mut [f32]) {
+    let sum: f32 = votes.iter().sum();
+    if sum > 0.0 {
+        for v in votes.iter_mut() {
+            *v /= sum;
+        }
+    }
+}
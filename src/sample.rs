@@ -0,0 +1,180 @@
+//! Elections with a finite number of voters, sampled at random.
+
+use std::collections::BTreeMap;
+
+use crate::electorate::{Kernel, Weights};
+use crate::space::Projection;
+use crate::strategy;
+use crate::tie::splitmix64;
+use crate::{
+    cast_ballots, check_strategy, validate, ElectionError, ElectionMethod, Integration, Point,
+    Settings, Strategy,
+};
+
+/// How many voters to sample around each point of the map, and how often.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Sampling {
+    /// Number of voters drawn from the electorate around each point.
+    pub num_voters: usize,
+    /// Number of independent elections to run.
+    pub repeats: usize,
+    /// The same seed always draws the same voters.
+    pub seed: u64,
+}
+
+impl Default for Sampling {
+    fn default() -> Sampling {
+        Sampling {
+            num_voters: 1000,
+            repeats: 16,
+            seed: 0,
+        }
+    }
+}
+
+/// How often each candidate won the repeated elections of a finite electorate.
+///
+/// Per-point and per-candidate values are laid out as in `ElectionResult`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampledResult {
+    /// Width and height of the map, in pixels.
+    pub size: usize,
+    pub num_candidates: usize,
+    /// Id of the election method.
+    pub method: String,
+    /// Share of the elections each candidate won; one value per point and candidate.
+    pub frequencies: Vec<f32>,
+}
+
+impl SampledResult {
+    /// The share of the elections each candidate won at pixel (x, y).
+    pub fn frequencies_at(&self, x: usize, y: usize) -> &[f32] {
+        let i = (x * self.size + y) * self.num_candidates;
+        &self.frequencies[i..i + self.num_candidates]
+    }
+
+    /// The candidate that won the most elections at each point, the first one if several did.
+    pub fn winners(&self) -> impl Iterator<Item = usize> + '_ {
+        self.frequencies.chunks(self.num_candidates).map(|wins| {
+            let most = wins.iter().cloned().fold(0.0, f32::max);
+            wins.iter().position(|&w| w == most).unwrap_or(0)
+        })
+    }
+
+    /// Share of the elections that the most frequent winner lost at each point, from 0 where
+    /// the outcome is certain; one value per point.
+    pub fn uncertainty(&self) -> Vec<f32> {
+        self.frequencies
+            .chunks(self.num_candidates)
+            .map(|wins| 1.0 - wins.iter().cloned().fold(0.0, f32::max))
+            .collect()
+    }
+}
+
+/// Simulates `sampling.repeats` elections at every point of a `size` x `size` map, each with
+/// `sampling.num_voters` voters drawn at random from the electorate around the point.
+///
+/// Voters are drawn from the same grid of locations that `election` integrates over, so the
+/// winners converge to those of `election` as the number of voters grows. Every point draws
+/// its voters at the same offsets in a given election. Voters at a location are still spread
+/// over the hidden issues, the draws of a random voter model and the strategic share, as in
+/// `election`.
+pub fn sample_election(
+    size: i32,
+    candidates: &[Point],
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+    sampling: &Sampling,
+) -> Result<SampledResult, ElectionError> {
+    validate(size, candidates, settings)?;
+    if sampling.num_voters == 0 {
+        return Err(ElectionError::ZeroVoters);
+    }
+    if sampling.repeats == 0 {
+        return Err(ElectionError::ZeroRepeats);
+    }
+    if settings.integration == Integration::Exact {
+        return Err(ElectionError::ExactUnsupported("sampled electorates"));
+    }
+    check_strategy(method, settings)?;
+
+    // Ballots only depend on where voters are, so they are cast once for all elections.
+    let projection = Projection::plane(candidates);
+    let mut ballots = cast_ballots(size, (0, 0), size, &projection, method, settings);
+    let population = Population::new(&ballots.kernels);
+
+    let num_candidates = candidates.len();
+    let mut frequencies = vec![0f32; size.pow(2) as usize * num_candidates];
+    for repeat in 0..sampling.repeats {
+        let seed = splitmix64(sampling.seed ^ splitmix64(repeat as u64));
+        ballots.set_kernels(vec![population.sample(sampling.num_voters, seed)]);
+        let num_votes = method.tally(&ballots);
+        let mut winners = method.select_winners(&ballots, &num_votes);
+        if settings.strategy != Strategy::Sincere {
+            winners = strategy::vote(
+                &projection,
+                method,
+                settings,
+                &ballots,
+                &num_votes,
+                &winners,
+            )
+            .1;
+        }
+        for (point, outcome) in winners.outcomes.iter().enumerate() {
+            frequencies[point * num_candidates + outcome.winner] += 1.0 / sampling.repeats as f32;
+        }
+    }
+    Ok(SampledResult {
+        size: size as usize,
+        num_candidates,
+        method: method.id().to_string(),
+        frequencies,
+    })
+}
+
+/// Every voter around a point, ready to be sampled.
+struct Population {
+    voters: Vec<(i32, i32, f32)>,
+    /// The total weight of the voters up to and including each voter.
+    cumulative: Vec<f64>,
+}
+
+impl Population {
+    fn new(kernels: &[Kernel]) -> Population {
+        let voters: Vec<(i32, i32, f32)> = kernels.iter().flat_map(Kernel::voters).collect();
+        let cumulative = voters
+            .iter()
+            .scan(0f64, |total, &(_, _, p)| {
+                *total += p as f64;
+                Some(*total)
+            })
+            .collect();
+        Population { voters, cumulative }
+    }
+
+    /// A kernel of `num_voters` voters drawn at random, with the same total weight.
+    fn sample(&self, num_voters: usize, seed: u64) -> Kernel {
+        let total = self.cumulative.last().cloned().unwrap_or(0.0);
+        let weight = (total / num_voters as f64) as f32;
+        let mut counts = BTreeMap::new();
+        for voter in 0..num_voters {
+            let hash = splitmix64(seed ^ splitmix64(voter as u64));
+            let u = ((hash >> 11) as f64 + 0.5) / (1u64 << 53) as f64;
+            let i = self.cumulative.partition_point(|&c| c < u * total);
+            let (dx, dy, _) = self.voters[i.min(self.voters.len() - 1)];
+            *counts.entry((dx, dy)).or_insert(0.0) += weight;
+        }
+        let range = counts
+            .keys()
+            .map(|&(dx, dy)| dx.abs().max(dy.abs()))
+            .max()
+            .unwrap_or(0);
+        let weights = counts.into_iter().map(|((dx, dy), p)| (dx, dy, p));
+        Kernel {
+            weights: Weights::Full(weights.collect()),
+            offset: (0, 0),
+            range,
+        }
+    }
+}
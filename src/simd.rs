@@ -0,0 +1,466 @@
+//! Vector kernels for the innermost loops of the vote counts: AVX or SSE2 on x86, simd128 in
+//! wasm builds that enable it, and plain loops everywhere else, or with the `scalar` feature.
+//!
+//! Each kernel computes every value with the same operations in the same order as the plain
+//! loop, several values at a time, so maps are bit for bit the same whichever kernel runs. Lanes
+//! that the plain loop would skip add zero instead, which vote totals, never negative, don't
+//! notice.
+
+use crate::fft::Complex;
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(feature = "scalar")
+))]
+use x86 as arch;
+
+#[cfg(all(
+    target_arch = "wasm32",
+    target_feature = "simd128",
+    not(feature = "scalar")
+))]
+use wasm as arch;
+
+#[cfg(not(any(
+    all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2",
+        not(feature = "scalar")
+    ),
+    all(
+        target_arch = "wasm32",
+        target_feature = "simd128",
+        not(feature = "scalar")
+    )
+)))]
+use scalar as arch;
+
+/// Adds `values` to `totals`, in double precision.
+pub(crate) fn add_widened(totals: &mut [f64], values: &[f32]) {
+    assert_eq!(totals.len(), values.len());
+    arch::add_widened(totals, values);
+}
+
+/// Multiplies every value by the weight at the same index.
+pub(crate) fn mul_complex(values: &mut [Complex], weights: &[Complex]) {
+    assert_eq!(values.len(), weights.len());
+    arch::mul_complex(values, weights);
+}
+
+/// Adds every value times the weight at the same index to `sums`.
+pub(crate) fn mul_add_complex(sums: &mut [Complex], values: &[Complex], weights: &[Complex]) {
+    assert_eq!(sums.len(), values.len());
+    assert_eq!(sums.len(), weights.len());
+    arch::mul_add_complex(sums, values, weights);
+}
+
+/// Adds `weight` to the votes of every candidate marked on an approval ballot.
+pub(crate) fn add_approved(votes: &mut [f32], marks: &[u8], weight: f32) {
+    assert_eq!(votes.len(), marks.len());
+    arch::add_approved(votes, marks, weight);
+}
+
+/// Adds `weight` to the votes of every candidate ranked below `rank` on a ranked ballot, unranked
+/// candidates included. `rank` is a rank on the ballot, not `UNRANKED`.
+pub(crate) fn add_ranked_below(votes: &mut [f32], ranks: &[u8], rank: u8, weight: f32) {
+    assert_eq!(votes.len(), ranks.len());
+    assert_ne!(rank, 0);
+    arch::add_ranked_below(votes, ranks, rank, weight);
+}
+
+/// Adds the Borda points of a ranked ballot times `weight` to the votes: one point for every
+/// rank below that of the candidate, and none for unranked candidates.
+pub(crate) fn add_borda(votes: &mut [f32], ranks: &[u8], weight: f32) {
+    assert_eq!(votes.len(), ranks.len());
+    arch::add_borda(votes, ranks, weight);
+}
+
+/// The plain loops, which the vector kernels also use for the last few values.
+#[allow(dead_code)]
+mod scalar {
+    use crate::fft::Complex;
+
+    pub fn add_widened(totals: &mut [f64], values: &[f32]) {
+        for (total, value) in totals.iter_mut().zip(values) {
+            *total += *value as f64;
+        }
+    }
+
+    pub fn mul_complex(values: &mut [Complex], weights: &[Complex]) {
+        for (value, weight) in values.iter_mut().zip(weights) {
+            *value = *value * *weight;
+        }
+    }
+
+    pub fn mul_add_complex(sums: &mut [Complex], values: &[Complex], weights: &[Complex]) {
+        for ((sum, value), weight) in sums.iter_mut().zip(values).zip(weights) {
+            *sum = *sum + *value * *weight;
+        }
+    }
+
+    pub fn add_approved(votes: &mut [f32], marks: &[u8], weight: f32) {
+        for (vote, &mark) in votes.iter_mut().zip(marks) {
+            if mark != 0 {
+                *vote += weight;
+            }
+        }
+    }
+
+    pub fn add_ranked_below(votes: &mut [f32], ranks: &[u8], rank: u8, weight: f32) {
+        for (vote, &other) in votes.iter_mut().zip(ranks) {
+            if other == 0 || rank < other {
+                *vote += weight;
+            }
+        }
+    }
+
+    /// `num_candidates` is the length of the whole ballot, of which `ranks` may be the end.
+    pub fn add_borda_of(votes: &mut [f32], ranks: &[u8], num_candidates: u8, weight: f32) {
+        for (vote, &rank) in votes.iter_mut().zip(ranks) {
+            if rank != 0 {
+                *vote += (num_candidates - rank) as f32 * weight;
+            }
+        }
+    }
+
+    pub fn add_borda(votes: &mut [f32], ranks: &[u8], weight: f32) {
+        add_borda_of(votes, ranks, ranks.len() as u8, weight);
+    }
+}
+
+#[cfg(all(
+    any(target_arch = "x86", target_arch = "x86_64"),
+    target_feature = "sse2",
+    not(feature = "scalar")
+))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::*;
+
+    use super::scalar;
+    use crate::fft::Complex;
+
+    /// Shorter loops, like those over the candidates of a ballot, stay with SSE2, which can be
+    /// inlined into them, unlike the AVX kernels.
+    const AVX_MIN_LEN: usize = 32;
+
+    pub fn add_widened(totals: &mut [f64], values: &[f32]) {
+        if totals.len() >= AVX_MIN_LEN && is_x86_feature_detected!("avx") {
+            // SAFETY: the CPU supports AVX, and the caller checked that the lengths match.
+            unsafe { add_widened_avx(totals, values) }
+        } else {
+            // SAFETY: SSE2 is enabled at compile time, and the caller checked the lengths.
+            unsafe { add_widened_sse2(totals, values) }
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    unsafe fn add_widened_avx(totals: &mut [f64], values: &[f32]) {
+        let end = totals.len() / 4 * 4;
+        for i in (0..end).step_by(4) {
+            let total = _mm256_loadu_pd(totals.as_ptr().add(i));
+            let value = _mm256_cvtps_pd(_mm_loadu_ps(values.as_ptr().add(i)));
+            _mm256_storeu_pd(totals.as_mut_ptr().add(i), _mm256_add_pd(total, value));
+        }
+        scalar::add_widened(&mut totals[end..], &values[end..]);
+    }
+
+    unsafe fn add_widened_sse2(totals: &mut [f64], values: &[f32]) {
+        let end = totals.len() / 2 * 2;
+        for i in (0..end).step_by(2) {
+            let total = _mm_loadu_pd(totals.as_ptr().add(i));
+            let pair = _mm_loadl_epi64(values.as_ptr().add(i) as *const __m128i);
+            let value = _mm_cvtps_pd(_mm_castsi128_ps(pair));
+            _mm_storeu_pd(totals.as_mut_ptr().add(i), _mm_add_pd(total, value));
+        }
+        scalar::add_widened(&mut totals[end..], &values[end..]);
+    }
+
+    /// `a * b` for complex values stored as `[re, im]`: `[a.re * b.re - a.im * b.im,
+    /// a.re * b.im + a.im * b.re]`, subtracting by adding the negated product.
+    #[inline]
+    unsafe fn mul_sse2(a: __m128d, b: __m128d) -> __m128d {
+        let re = _mm_unpacklo_pd(a, a);
+        let im = _mm_unpackhi_pd(a, a);
+        let swapped = _mm_shuffle_pd(b, b, 1);
+        let negate_re = _mm_set_pd(0.0, -0.0);
+        _mm_add_pd(
+            _mm_mul_pd(re, b),
+            _mm_xor_pd(_mm_mul_pd(im, swapped), negate_re),
+        )
+    }
+
+    /// Two complex products at once, like `mul_sse2`.
+    #[inline]
+    #[target_feature(enable = "avx")]
+    unsafe fn mul_avx(a: __m256d, b: __m256d) -> __m256d {
+        let re = _mm256_movedup_pd(a);
+        let im = _mm256_permute_pd(a, 0b1111);
+        let swapped = _mm256_permute_pd(b, 0b0101);
+        _mm256_addsub_pd(_mm256_mul_pd(re, b), _mm256_mul_pd(im, swapped))
+    }
+
+    pub fn mul_complex(values: &mut [Complex], weights: &[Complex]) {
+        if is_x86_feature_detected!("avx") {
+            // SAFETY: the CPU supports AVX, and the caller checked that the lengths match.
+            unsafe { mul_complex_avx(values, weights) }
+        } else {
+            // SAFETY: SSE2 is enabled at compile time, and the caller checked the lengths.
+            unsafe { mul_complex_sse2(values, weights) }
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    unsafe fn mul_complex_avx(values: &mut [Complex], weights: &[Complex]) {
+        let end = values.len() / 2 * 2;
+        let value_ptr = values.as_mut_ptr() as *mut f64;
+        let weight_ptr = weights.as_ptr() as *const f64;
+        for i in (0..2 * end).step_by(4) {
+            let value = _mm256_loadu_pd(value_ptr.add(i));
+            let weight = _mm256_loadu_pd(weight_ptr.add(i));
+            _mm256_storeu_pd(value_ptr.add(i), mul_avx(value, weight));
+        }
+        scalar::mul_complex(&mut values[end..], &weights[end..]);
+    }
+
+    unsafe fn mul_complex_sse2(values: &mut [Complex], weights: &[Complex]) {
+        let value_ptr = values.as_mut_ptr() as *mut f64;
+        let weight_ptr = weights.as_ptr() as *const f64;
+        for i in (0..2 * values.len()).step_by(2) {
+            let value = _mm_loadu_pd(value_ptr.add(i));
+            let weight = _mm_loadu_pd(weight_ptr.add(i));
+            _mm_storeu_pd(value_ptr.add(i), mul_sse2(value, weight));
+        }
+    }
+
+    pub fn mul_add_complex(sums: &mut [Complex], values: &[Complex], weights: &[Complex]) {
+        if is_x86_feature_detected!("avx") {
+            // SAFETY: the CPU supports AVX, and the caller checked that the lengths match.
+            unsafe { mul_add_complex_avx(sums, values, weights) }
+        } else {
+            // SAFETY: SSE2 is enabled at compile time, and the caller checked the lengths.
+            unsafe { mul_add_complex_sse2(sums, values, weights) }
+        }
+    }
+
+    #[target_feature(enable = "avx")]
+    unsafe fn mul_add_complex_avx(sums: &mut [Complex], values: &[Complex], weights: &[Complex]) {
+        let end = sums.len() / 2 * 2;
+        let sum_ptr = sums.as_mut_ptr() as *mut f64;
+        let value_ptr = values.as_ptr() as *const f64;
+        let weight_ptr = weights.as_ptr() as *const f64;
+        for i in (0..2 * end).step_by(4) {
+            let sum = _mm256_loadu_pd(sum_ptr.add(i));
+            let value = _mm256_loadu_pd(value_ptr.add(i));
+            let weight = _mm256_loadu_pd(weight_ptr.add(i));
+            _mm256_storeu_pd(sum_ptr.add(i), _mm256_add_pd(sum, mul_avx(value, weight)));
+        }
+        scalar::mul_add_complex(&mut sums[end..], &values[end..], &weights[end..]);
+    }
+
+    unsafe fn mul_add_complex_sse2(sums: &mut [Complex], values: &[Complex], weights: &[Complex]) {
+        let sum_ptr = sums.as_mut_ptr() as *mut f64;
+        let value_ptr = values.as_ptr() as *const f64;
+        let weight_ptr = weights.as_ptr() as *const f64;
+        for i in (0..2 * sums.len()).step_by(2) {
+            let sum = _mm_loadu_pd(sum_ptr.add(i));
+            let value = _mm_loadu_pd(value_ptr.add(i));
+            let weight = _mm_loadu_pd(weight_ptr.add(i));
+            _mm_storeu_pd(sum_ptr.add(i), _mm_add_pd(sum, mul_sse2(value, weight)));
+        }
+    }
+
+    /// Four bytes of a ballot from index `i` on, as 32 bit integers.
+    #[inline]
+    unsafe fn load_bytes(bytes: &[u8], i: usize) -> __m128i {
+        let word = u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        let zero = _mm_setzero_si128();
+        let word = _mm_cvtsi32_si128(word as i32);
+        _mm_unpacklo_epi16(_mm_unpacklo_epi8(word, zero), zero)
+    }
+
+    /// Adds `weight` to the four votes from index `i` on where `mask` is set.
+    #[inline]
+    unsafe fn add_masked(votes: &mut [f32], i: usize, mask: __m128i, weight: __m128) {
+        let vote = _mm_loadu_ps(votes.as_ptr().add(i));
+        let weight = _mm_and_ps(_mm_castsi128_ps(mask), weight);
+        _mm_storeu_ps(votes.as_mut_ptr().add(i), _mm_add_ps(vote, weight));
+    }
+
+    pub fn add_approved(votes: &mut [f32], marks: &[u8], weight: f32) {
+        let end = votes.len() / 4 * 4;
+        // SAFETY: SSE2 is enabled at compile time, and the caller checked that the lengths match.
+        unsafe {
+            let zero = _mm_setzero_si128();
+            let all = _mm_set1_epi32(-1);
+            let weight_x4 = _mm_set1_ps(weight);
+            for i in (0..end).step_by(4) {
+                let unmarked = _mm_cmpeq_epi32(load_bytes(marks, i), zero);
+                add_masked(votes, i, _mm_xor_si128(unmarked, all), weight_x4);
+            }
+        }
+        scalar::add_approved(&mut votes[end..], &marks[end..], weight);
+    }
+
+    pub fn add_ranked_below(votes: &mut [f32], ranks: &[u8], rank: u8, weight: f32) {
+        let end = votes.len() / 4 * 4;
+        // SAFETY: SSE2 is enabled at compile time, and the caller checked that the lengths match.
+        unsafe {
+            // Ranks less one, with unranked candidates wrapping around to the bottom.
+            let last = _mm_set1_epi32(0xff);
+            let rank_x4 = _mm_set1_epi32(rank as i32 - 1);
+            let weight_x4 = _mm_set1_ps(weight);
+            for i in (0..end).step_by(4) {
+                let others = _mm_and_si128(_mm_add_epi32(load_bytes(ranks, i), last), last);
+                add_masked(votes, i, _mm_cmpgt_epi32(others, rank_x4), weight_x4);
+            }
+        }
+        scalar::add_ranked_below(&mut votes[end..], &ranks[end..], rank, weight);
+    }
+
+    pub fn add_borda(votes: &mut [f32], ranks: &[u8], weight: f32) {
+        let end = votes.len() / 4 * 4;
+        let num_candidates = ranks.len() as u8;
+        // SAFETY: SSE2 is enabled at compile time, and the caller checked that the lengths match.
+        unsafe {
+            let zero = _mm_setzero_si128();
+            let num_candidates_x4 = _mm_set1_epi32(num_candidates as i32);
+            let weight_x4 = _mm_set1_ps(weight);
+            for i in (0..end).step_by(4) {
+                let rank = load_bytes(ranks, i);
+                let unranked = _mm_cmpeq_epi32(rank, zero);
+                let points = _mm_andnot_si128(unranked, _mm_sub_epi32(num_candidates_x4, rank));
+                let vote = _mm_loadu_ps(votes.as_ptr().add(i));
+                let points = _mm_mul_ps(_mm_cvtepi32_ps(points), weight_x4);
+                _mm_storeu_ps(votes.as_mut_ptr().add(i), _mm_add_ps(vote, points));
+            }
+        }
+        scalar::add_borda_of(&mut votes[end..], &ranks[end..], num_candidates, weight);
+    }
+}
+
+#[cfg(all(
+    target_arch = "wasm32",
+    target_feature = "simd128",
+    not(feature = "scalar")
+))]
+mod wasm {
+    use std::arch::wasm32::*;
+
+    use super::scalar;
+    use crate::fft::Complex;
+
+    pub fn add_widened(totals: &mut [f64], values: &[f32]) {
+        let end = totals.len() / 2 * 2;
+        for i in (0..end).step_by(2) {
+            // SAFETY: the caller checked that the lengths match, and `i + 2 <= end`.
+            unsafe {
+                let total_ptr = totals.as_mut_ptr().add(i) as *mut v128;
+                let pair = v128_load64_zero(values.as_ptr().add(i) as *const u64);
+                let total = f64x2_add(v128_load(total_ptr), f64x2_promote_low_f32x4(pair));
+                v128_store(total_ptr, total);
+            }
+        }
+        scalar::add_widened(&mut totals[end..], &values[end..]);
+    }
+
+    /// `a * b` for complex values stored as `[re, im]`: `[a.re * b.re - a.im * b.im,
+    /// a.re * b.im + a.im * b.re]`, subtracting by adding the negated product.
+    #[inline]
+    fn mul(a: v128, b: v128) -> v128 {
+        let re = i64x2_shuffle::<0, 0>(a, a);
+        let im = i64x2_shuffle::<1, 1>(a, a);
+        let swapped = i64x2_shuffle::<1, 0>(b, b);
+        let negate_re = f64x2(-0.0, 0.0);
+        f64x2_add(
+            f64x2_mul(re, b),
+            v128_xor(f64x2_mul(im, swapped), negate_re),
+        )
+    }
+
+    pub fn mul_complex(values: &mut [Complex], weights: &[Complex]) {
+        for (value, weight) in values.iter_mut().zip(weights) {
+            // SAFETY: `Complex` is two `f64`, as large as a `v128`.
+            unsafe {
+                let value = value as *mut Complex as *mut v128;
+                let weight = v128_load(weight as *const Complex as *const v128);
+                v128_store(value, mul(v128_load(value), weight));
+            }
+        }
+    }
+
+    pub fn mul_add_complex(sums: &mut [Complex], values: &[Complex], weights: &[Complex]) {
+        for ((sum, value), weight) in sums.iter_mut().zip(values).zip(weights) {
+            // SAFETY: `Complex` is two `f64`, as large as a `v128`.
+            unsafe {
+                let sum = sum as *mut Complex as *mut v128;
+                let value = v128_load(value as *const Complex as *const v128);
+                let weight = v128_load(weight as *const Complex as *const v128);
+                v128_store(sum, f64x2_add(v128_load(sum), mul(value, weight)));
+            }
+        }
+    }
+
+    /// Four bytes of a ballot from index `i` on, as 32 bit integers.
+    #[inline]
+    fn load_bytes(bytes: &[u8], i: usize) -> v128 {
+        let word = u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+        u32x4_extend_low_u16x8(u16x8_extend_low_u8x16(u32x4(word, 0, 0, 0)))
+    }
+
+    /// Adds `weight` to the four votes from index `i` on where `mask` is set.
+    #[inline]
+    fn add_masked(votes: &mut [f32], i: usize, mask: v128, weight: v128) {
+        // SAFETY: callers only pass `i + 4 <= votes.len()`.
+        unsafe {
+            let vote_ptr = votes.as_mut_ptr().add(i) as *mut v128;
+            let vote = f32x4_add(v128_load(vote_ptr), v128_and(mask, weight));
+            v128_store(vote_ptr, vote);
+        }
+    }
+
+    pub fn add_approved(votes: &mut [f32], marks: &[u8], weight: f32) {
+        let end = votes.len() / 4 * 4;
+        let weight_x4 = f32x4_splat(weight);
+        for i in (0..end).step_by(4) {
+            let marked = i32x4_ne(load_bytes(marks, i), i32x4_splat(0));
+            add_masked(votes, i, marked, weight_x4);
+        }
+        scalar::add_approved(&mut votes[end..], &marks[end..], weight);
+    }
+
+    pub fn add_ranked_below(votes: &mut [f32], ranks: &[u8], rank: u8, weight: f32) {
+        let end = votes.len() / 4 * 4;
+        // Ranks less one, with unranked candidates wrapping around to the bottom.
+        let last = i32x4_splat(0xff);
+        let rank_x4 = i32x4_splat(rank as i32 - 1);
+        let weight_x4 = f32x4_splat(weight);
+        for i in (0..end).step_by(4) {
+            let others = v128_and(i32x4_add(load_bytes(ranks, i), last), last);
+            add_masked(votes, i, i32x4_gt(others, rank_x4), weight_x4);
+        }
+        scalar::add_ranked_below(&mut votes[end..], &ranks[end..], rank, weight);
+    }
+
+    pub fn add_borda(votes: &mut [f32], ranks: &[u8], weight: f32) {
+        let end = votes.len() / 4 * 4;
+        let num_candidates = ranks.len() as u8;
+        let num_candidates_x4 = i32x4_splat(num_candidates as i32);
+        let weight_x4 = f32x4_splat(weight);
+        for i in (0..end).step_by(4) {
+            let rank = load_bytes(ranks, i);
+            let ranked = i32x4_ne(rank, i32x4_splat(0));
+            let points = v128_and(ranked, i32x4_sub(num_candidates_x4, rank));
+            let points = f32x4_mul(f32x4_convert_i32x4(points), weight_x4);
+            // SAFETY: `i + 4 <= end <= votes.len()`.
+            unsafe {
+                let vote_ptr = votes.as_mut_ptr().add(i) as *mut v128;
+                v128_store(vote_ptr, f32x4_add(v128_load(vote_ptr), points));
+            }
+        }
+        scalar::add_borda_of(&mut votes[end..], &ranks[end..], num_candidates, weight);
+    }
+}
@@ -0,0 +1,175 @@
+//! Issue spaces with more dimensions than the two shown on the map.
+
+use crate::electorate::normal_pdf;
+use crate::{ElectionError, Point, MAX_HIDDEN_POSITIONS};
+
+/// An issue that is not shown on the map. Voters are normally distributed along it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HiddenIssue {
+    /// Position of the center of the electorate along the issue, the same at every point of the
+    /// map.
+    pub center: f32,
+    /// Standard deviation of voters along the issue.
+    pub sigma: f32,
+}
+
+/// A plane through the issue space, shown on the map.
+///
+/// Two issues run along the axes of the map; the electorate is centered at a fixed position along
+/// all the others, and spreads out along them as well.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slice {
+    /// The issues along the x and y axes of the map.
+    pub axes: (usize, usize),
+    /// Every issue but the two axes, in order.
+    pub hidden: Vec<HiddenIssue>,
+    /// Number of positions voters are sampled at along each hidden issue, within `num_sigma`
+    /// standard deviations of the center.
+    pub samples: usize,
+}
+
+impl Default for Slice {
+    /// The plane of a two dimensional issue space.
+    fn default() -> Slice {
+        Slice {
+            axes: (0, 1),
+            hidden: vec![],
+            samples: 9,
+        }
+    }
+}
+
+impl Slice {
+    /// The slice through the issues `axes` of a space where the electorate is centered at
+    /// `center` and spreads out by `sigma` along every hidden issue. The coordinates of `center`
+    /// along `axes` are ignored.
+    pub fn through(axes: (usize, usize), center: &[f32], sigma: f32) -> Slice {
+        let hidden = (0..center.len())
+            .filter(|&issue| issue != axes.0 && issue != axes.1)
+            .map(|issue| HiddenIssue {
+                center: center[issue],
+                sigma,
+            })
+            .collect();
+        Slice {
+            axes,
+            hidden,
+            ..Slice::default()
+        }
+    }
+
+    pub fn num_dimensions(&self) -> usize {
+        self.hidden.len() + 2
+    }
+
+    /// Splits a position in the issue space into its position on the map and its coordinates
+    /// along the hidden issues.
+    pub fn project(&self, position: &[f32]) -> (Point, Vec<f32>) {
+        let point = Point::new(position[self.axes.0], position[self.axes.1]);
+        let hidden = (0..position.len())
+            .filter(|&issue| issue != self.axes.0 && issue != self.axes.1)
+            .map(|issue| position[issue])
+            .collect();
+        (point, hidden)
+    }
+
+    /// Number of positions along the hidden issues that voters are sampled at.
+    fn num_positions(&self) -> usize {
+        self.samples
+            .checked_pow(self.hidden.len() as u32)
+            .unwrap_or(usize::MAX)
+    }
+
+    pub(crate) fn validate(&self, candidates: &[Vec<f32>]) -> Result<(), ElectionError> {
+        let dimensions = self.num_dimensions();
+        let (x, y) = self.axes;
+        if x == y || x >= dimensions || y >= dimensions {
+            return Err(ElectionError::InvalidAxes { x, y, dimensions });
+        }
+        if let Some(i) = candidates.iter().position(|c| c.len() != dimensions) {
+            return Err(ElectionError::DimensionMismatch {
+                candidate: i,
+                count: candidates[i].len(),
+                dimensions,
+            });
+        }
+        if let Some(i) = self.hidden.iter().position(|issue| {
+            !(issue.center.is_finite() && issue.sigma > 0.0 && issue.sigma.is_finite())
+        }) {
+            return Err(ElectionError::InvalidHiddenIssue(i));
+        }
+        if self.samples == 0 {
+            return Err(ElectionError::ZeroSamples);
+        }
+        if self.num_positions() > MAX_HIDDEN_POSITIONS {
+            return Err(ElectionError::TooManyHiddenPositions {
+                count: self.num_positions(),
+                max: MAX_HIDDEN_POSITIONS,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Candidates in a slice of the issue space, and where voters are sampled along the hidden issues.
+pub(crate) struct Projection {
+    /// Position of each candidate on the map.
+    pub points: Vec<Point>,
+    /// Coordinates of each candidate along the hidden issues, one candidate after the other.
+    pub hidden: Vec<f32>,
+    /// Coordinates of voters along the hidden issues, with the share of voters there.
+    pub positions: Vec<(Vec<f32>, f32)>,
+}
+
+impl Projection {
+    /// Candidates in a two dimensional issue space.
+    pub fn plane(candidates: &[Point]) -> Projection {
+        Projection {
+            points: candidates.to_vec(),
+            hidden: vec![],
+            positions: vec![(vec![], 1.0)],
+        }
+    }
+
+    /// Projects candidates onto `slice`, sampling voters within `num_sigma` standard deviations
+    /// along the hidden issues.
+    pub fn new(candidates: &[Vec<f32>], slice: &Slice, num_sigma: f32) -> Projection {
+        let mut points = vec![];
+        let mut hidden = vec![];
+        for position in candidates {
+            let (point, coordinates) = slice.project(position);
+            points.push(point);
+            hidden.extend(coordinates);
+        }
+
+        // Midpoint rule along each issue, then every combination of the samples of each issue.
+        let step = 2.0 * num_sigma / slice.samples as f32;
+        let samples: Vec<f32> = (0..slice.samples)
+            .map(|k| -num_sigma + (k as f32 + 0.5) * step)
+            .collect();
+        let densities: Vec<f32> = samples.iter().map(|&u| normal_pdf(0.0, 1.0, u)).collect();
+        let total: f32 = densities.iter().sum();
+        let mut positions = vec![(vec![], 1.0)];
+        for issue in slice.hidden.iter() {
+            positions = positions
+                .iter()
+                .flat_map(|(position, share): &(Vec<f32>, f32)| {
+                    samples.iter().zip(&densities).map(move |(u, density)| {
+                        let mut position = position.clone();
+                        position.push(issue.center + u * issue.sigma);
+                        (position, share * density / total)
+                    })
+                })
+                .collect();
+        }
+        Projection {
+            points,
+            hidden,
+            positions,
+        }
+    }
+
+    pub fn num_hidden(&self) -> usize {
+        self.positions[0].0.len()
+    }
+}
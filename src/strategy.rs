@@ -0,0 +1,250 @@
+//! Voters who adapt their ballots to a poll of the sincere election.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::ballot::{BallotMut, BallotType};
+use crate::space::Projection;
+use crate::{recast_ballots, Ballots, ElectionMethod, Outcome, Settings, Voter, Winners};
+
+/// How voters adapt their ballots to a poll of the sincere election at their point of the map.
+///
+/// The poll names the frontrunner, who wins the sincere election, and the runner-up, who comes
+/// second in its final round.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Voters fill in their ballots sincerely.
+    #[default]
+    Sincere,
+    /// Voters rank whichever of the frontrunner and the runner-up they prefer first.
+    Compromise,
+    /// Voters rank whichever of the frontrunner and the runner-up they like less last.
+    Bury,
+    /// Voters approve of every candidate they prefer to the frontrunner, and of the frontrunner
+    /// if they prefer it to the runner-up.
+    ApproveFrontrunner,
+    /// Supporters of the frontrunner rank the weakest candidate of the first round first, hoping
+    /// that it knocks the runner-up out of an instant runoff.
+    Pushover,
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    /// Parses `sincere`, `compromise`, `bury`, `frontrunner` or `pushover`.
+    fn from_str(s: &str) -> Result<Strategy, String> {
+        match s {
+            "sincere" => Ok(Strategy::Sincere),
+            "compromise" => Ok(Strategy::Compromise),
+            "bury" => Ok(Strategy::Bury),
+            "frontrunner" => Ok(Strategy::ApproveFrontrunner),
+            "pushover" => Ok(Strategy::Pushover),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+impl Strategy {
+    /// Whether this strategy adapts ballots of this type. Sincere voters cast any ballot.
+    pub fn applies_to(self, ballot_type: BallotType) -> bool {
+        match self {
+            Strategy::Sincere => true,
+            Strategy::Compromise => ballot_type != BallotType::Approval,
+            Strategy::Bury | Strategy::Pushover => ballot_type == BallotType::Ranking,
+            Strategy::ApproveFrontrunner => ballot_type == BallotType::Approval,
+        }
+    }
+
+    /// Adapts the sincere ballot of `voter` to `poll`.
+    pub(crate) fn apply(self, voter: &Voter, poll: &Poll, ballot: BallotMut) {
+        let (frontrunner, runner_up) = (poll.frontrunner, poll.runner_up);
+        if frontrunner == runner_up {
+            return;
+        }
+        let prefers = |a: usize, b: usize| voter.measure(a) < voter.measure(b);
+        let (liked, disliked) = if prefers(runner_up, frontrunner) {
+            (runner_up, frontrunner)
+        } else {
+            (frontrunner, runner_up)
+        };
+        match self {
+            Strategy::Sincere => {}
+            Strategy::Compromise => match ballot {
+                BallotMut::Single(mut ballot) => ballot.choose(liked),
+                ballot => ballot.into_ranking().promote(liked),
+            },
+            Strategy::Bury => ballot.into_ranking().demote(disliked),
+            Strategy::ApproveFrontrunner => {
+                let mut ballot = ballot.into_approval();
+                for c in 0..voter.num_candidates() {
+                    if prefers(c, frontrunner) || (c == frontrunner && liked == frontrunner) {
+                        ballot.approve(c);
+                    } else {
+                        ballot.disapprove(c);
+                    }
+                }
+            }
+            Strategy::Pushover => {
+                let favorite = (0..voter.num_candidates()).all(|c| !prefers(c, frontrunner));
+                if let (true, Some(weakest)) = (favorite, poll.weakest) {
+                    ballot.into_ranking().promote(weakest);
+                }
+            }
+        }
+    }
+}
+
+/// The outcome of the sincere election at a point of the map, as strategic voters see it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct Poll {
+    pub frontrunner: usize,
+    /// The frontrunner as well if there is no other candidate.
+    pub runner_up: usize,
+    /// The candidate with the fewest votes in the first round, other than the frontrunner and the
+    /// runner-up.
+    pub weakest: Option<usize>,
+}
+
+impl Poll {
+    /// The poll at each point of the map, from the vote totals of the first round and the winners
+    /// of the sincere election.
+    pub fn at_each_point(num_votes: &[f32], winners: &Winners) -> Vec<Poll> {
+        let num_candidates = num_votes.len() / winners.outcomes.len();
+        let first_rounds = num_votes.chunks(num_candidates);
+        first_rounds
+            .zip(winners.outcomes.iter())
+            .enumerate()
+            .map(|(point, (first, outcome))| {
+                let i = point * num_candidates;
+                let last = match winners.num_rounds[point] {
+                    1 => first,
+                    rounds => &winners.later_rounds[rounds - 2][i..i + num_candidates],
+                };
+                Poll::new(*outcome, first, last)
+            })
+            .collect()
+    }
+
+    fn new(outcome: Outcome, first: &[f32], last: &[f32]) -> Poll {
+        let frontrunner = outcome.winner;
+        let best = |votes: &[f32], excluded: &[usize]| {
+            (0..votes.len()).filter(|c| !excluded.contains(c)).fold(
+                None,
+                |best: Option<usize>, c| match best {
+                    Some(b) if votes[b] >= votes[c] => Some(b),
+                    _ => Some(c),
+                },
+            )
+        };
+        let runner_up = best(last, &[frontrunner]).unwrap_or(frontrunner);
+        let negated: Vec<f32> = first.iter().map(|v| -v).collect();
+        let weakest = best(&negated, &[frontrunner, runner_up]);
+        Poll {
+            frontrunner,
+            runner_up,
+            weakest,
+        }
+    }
+}
+
+/// Reruns the election at every point of the map of the `sincere` ballots with strategic voters,
+/// given the vote totals and winners of the sincere election.
+///
+/// Points with the same poll run the election together, on the smallest window of the map that
+/// covers all of them. Each poll costs casting the strategic ballots around its window and
+/// tallying the window, while the other voters keep their sincere ballots.
+/// Returns the vote totals, the winners and the share of exhausted ballots at each point.
+pub(crate) fn vote(
+    candidates: &Projection,
+    method: &dyn ElectionMethod,
+    settings: &Settings,
+    sincere: &Ballots,
+    num_votes: &[f32],
+    winners: &Winners,
+) -> (Vec<f32>, Winners, Vec<f32>) {
+    let size = sincere.size() as usize;
+    let num_candidates = candidates.points.len();
+    let num_points = winners.outcomes.len();
+    let mut groups: HashMap<Poll, Vec<usize>> = HashMap::new();
+    for (point, poll) in Poll::at_each_point(num_votes, winners)
+        .into_iter()
+        .enumerate()
+    {
+        // Only pushover cares about the weakest candidate; ignoring it saves elections.
+        let weakest = poll
+            .weakest
+            .filter(|_| settings.strategy == Strategy::Pushover);
+        let poll = Poll { weakest, ..poll };
+        groups.entry(poll).or_default().push(point);
+    }
+
+    let elections: Vec<_> = groups
+        .into_iter()
+        .map(|(poll, points)| {
+            let (mut low, mut high) = ((size, size), (0, 0));
+            for &point in points.iter() {
+                let (x, y) = (point / size, point % size);
+                low = (low.0.min(x), low.1.min(y));
+                high = (high.0.max(x), high.1.max(y));
+            }
+            let window = (high.0 - low.0).max(high.1 - low.1) + 1;
+            let corner = (low.0.min(size - window), low.1.min(size - window));
+            let origin = (
+                sincere.origin.0 + corner.0 as i32,
+                sincere.origin.1 + corner.1 as i32,
+            );
+            let ballots = recast_ballots(
+                sincere,
+                origin,
+                window as i32,
+                candidates,
+                method,
+                settings,
+                &poll,
+            );
+            let num_votes = method.tally(&ballots);
+            let winners = method.select_winners(&ballots, &num_votes);
+            let exhausted = ballots.exhausted(settings.truncation, &winners.eliminated);
+            let window_points: Vec<_> = points
+                .iter()
+                .map(|&point| (point / size - corner.0) * window + point % size - corner.1)
+                .collect();
+            (points, window_points, num_votes, winners, exhausted)
+        })
+        .collect();
+
+    // Merge the elections, repeating the final round of points decided early.
+    let num_later = elections
+        .iter()
+        .map(|(_, _, _, winners, _)| winners.later_rounds.len())
+        .max()
+        .unwrap_or(0);
+    let mut merged_votes = vec![0f32; num_points * num_candidates];
+    let mut merged = Winners {
+        outcomes: winners.outcomes.clone(),
+        later_rounds: vec![vec![0f32; num_points * num_candidates]; num_later],
+        num_rounds: vec![1; num_points],
+        eliminated: vec![0; num_points],
+    };
+    let mut merged_exhausted = vec![0f32; num_points];
+    for (points, window_points, num_votes, winners, exhausted) in elections.iter() {
+        for (&point, &w) in points.iter().zip(window_points) {
+            let i = point * num_candidates..(point + 1) * num_candidates;
+            let j = w * num_candidates..(w + 1) * num_candidates;
+            merged_votes[i.clone()].copy_from_slice(&num_votes[j.clone()]);
+            merged.outcomes[point] = winners.outcomes[w];
+            merged.num_rounds[point] = winners.num_rounds[w];
+            merged.eliminated[point] = winners.eliminated[w];
+            merged_exhausted[point] = exhausted[w];
+            for (round, totals) in merged.later_rounds.iter_mut().enumerate() {
+                let source = winners
+                    .later_rounds
+                    .get(round)
+                    .or_else(|| winners.later_rounds.last())
+                    .unwrap_or(num_votes);
+                totals[i.clone()].copy_from_slice(&source[j.clone()]);
+            }
+        }
+    }
+    (merged_votes, merged, merged_exhausted)
+}
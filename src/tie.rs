@@ -0,0 +1,145 @@
+//! Detecting and breaking ties between candidates.
+
+use std::str::FromStr;
+
+use serde::Serialize;
+
+/// Vote totals within this fraction of each other count as tied.
+///
+/// Totals are sums of many floating point values, so mathematically exact ties, like those on the
+/// symmetry axes of symmetric candidate layouts, usually differ in the last few bits.
+pub const TIE_TOLERANCE: f32 = 1e-6;
+
+/// How to pick a candidate when several are tied.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Pick the candidate with the lowest index.
+    #[default]
+    LowestIndex,
+    /// Pick a random candidate. The choice only depends on the seed, the point on the map and the
+    /// round, so maps are reproducible.
+    Random { seed: u64 },
+    /// Pick the candidate that did best in the previous round, for methods that have rounds.
+    PreviousRound,
+    /// Pick the candidate with the most Borda points, or the fewest when eliminating, for methods
+    /// with ranked ballots.
+    Borda,
+}
+
+impl FromStr for TieBreak {
+    type Err = String;
+
+    /// Parses `lowest`, `random`, `random:<seed>`, `previous` or `borda`.
+    fn from_str(s: &str) -> Result<TieBreak, String> {
+        match s {
+            "lowest" => Ok(TieBreak::LowestIndex),
+            "random" => Ok(TieBreak::Random { seed: 0 }),
+            "previous" => Ok(TieBreak::PreviousRound),
+            "borda" => Ok(TieBreak::Borda),
+            _ => match s.strip_prefix("random:").map(str::parse) {
+                Some(Ok(seed)) => Ok(TieBreak::Random { seed }),
+                _ => Err(s.to_string()),
+            },
+        }
+    }
+}
+
+/// The winner at a single point of the map.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Outcome {
+    pub winner: usize,
+    /// Whether a tie had to be broken to find the winner, in any round.
+    pub tied: bool,
+}
+
+/// Whether the method is looking for the candidate with the most or the fewest votes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Goal {
+    MostVotes,
+    FewestVotes,
+}
+
+impl Goal {
+    /// Whether `a` votes are better than `b` votes.
+    fn better(self, a: f32, b: f32) -> bool {
+        match self {
+            Goal::MostVotes => a > b,
+            Goal::FewestVotes => a < b,
+        }
+    }
+
+    /// Bitmask of the candidates not in `excluded` whose votes are closest to the goal, including
+    /// all candidates within `TIE_TOLERANCE` of the best one. NaN votes never lead.
+    pub fn leaders(self, votes: &[f32], excluded: u64) -> u64 {
+        let candidates = || {
+            votes
+                .iter()
+                .enumerate()
+                .filter(move |&(c, v)| (1 << c) & excluded == 0 && !v.is_nan())
+        };
+        let best = candidates()
+            .map(|(_, &v)| v)
+            .reduce(|a, b| if self.better(b, a) { b } else { a });
+        let best = match best {
+            Some(best) => best,
+            None => return 0,
+        };
+        let tolerance = best.abs() * TIE_TOLERANCE;
+        candidates()
+            .filter(|&(_, &v)| (v - best).abs() <= tolerance)
+            .fold(0, |leaders, (c, _)| leaders | 1 << c)
+    }
+
+    /// Narrows `tied` down to the candidates with the best `votes`.
+    fn narrow(self, tied: u64, votes: &[f32]) -> u64 {
+        match self.leaders(votes, !tied) {
+            0 => tied,
+            leaders => leaders,
+        }
+    }
+}
+
+/// Everything a tie break might need to know about a point of the map.
+pub(crate) struct TieContext<'a> {
+    /// Index of the point on the map.
+    pub point: usize,
+    /// Index of the round, for methods that have several.
+    pub round: usize,
+    /// Vote totals of the previous round.
+    pub previous: Option<&'a [f32]>,
+    /// Borda counts.
+    pub borda: Option<&'a [f32]>,
+}
+
+impl TieBreak {
+    /// Picks one of the candidates set in the non-empty `tied` bitmask.
+    pub(crate) fn choose(self, goal: Goal, tied: u64, context: &TieContext) -> usize {
+        let tied = match (self, context.previous, context.borda) {
+            (TieBreak::PreviousRound, Some(previous), _) => goal.narrow(tied, previous),
+            (TieBreak::Borda, _, Some(borda)) => goal.narrow(tied, borda),
+            _ => tied,
+        };
+        let pick = match self {
+            TieBreak::Random { seed } => {
+                let hash = splitmix64(
+                    seed ^ splitmix64(context.point as u64) ^ splitmix64(!(context.round as u64)),
+                );
+                (hash % tied.count_ones() as u64) as u32
+            }
+            _ => 0,
+        };
+        // Find the `pick`th set bit.
+        let mut remaining = tied;
+        for _ in 0..pick {
+            remaining &= remaining - 1;
+        }
+        remaining.trailing_zeros() as usize
+    }
+}
+
+pub(crate) fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,68 @@
+//! Voters who rank only some of the candidates.
+
+use std::str::FromStr;
+
+use crate::ballot::BallotMut;
+use crate::Voter;
+
+/// Which candidates voters bother to rank on a ranked ballot.
+///
+/// Candidates left off a ballot are preferred less than every ranked candidate. Once all the
+/// candidates a ballot ranks are eliminated, the ballot is exhausted. Voters who mark a single
+/// candidate leave it off the same way, abstaining instead. Approval ballots are filled in as
+/// usual.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Truncation {
+    /// Voters rank every candidate.
+    #[default]
+    Full,
+    /// Voters rank their `k` favorite candidates, along with any candidate tied with the last of
+    /// them.
+    TopK(usize),
+    /// Voters rank only the candidates within `radius` of themselves, and may rank none.
+    Radius(f32),
+}
+
+impl FromStr for Truncation {
+    type Err = String;
+
+    /// Parses `full`, `top:<k>` or `radius:<radius>`.
+    fn from_str(s: &str) -> Result<Truncation, String> {
+        if s == "full" {
+            return Ok(Truncation::Full);
+        }
+        let truncation = match s.split_once(':') {
+            Some(("top", k)) => k.parse().map(Truncation::TopK).ok(),
+            Some(("radius", radius)) => radius.parse().map(Truncation::Radius).ok(),
+            _ => None,
+        };
+        truncation.ok_or_else(|| s.to_string())
+    }
+}
+
+impl Truncation {
+    /// Leaves the candidates that `voter` doesn't bother with off their ranked ballot.
+    pub(crate) fn apply(self, voter: &Voter, ballot: BallotMut) {
+        let mut ranking = match (self, ballot) {
+            (Truncation::Full, _) => return,
+            (_, BallotMut::Ranking(ranking)) => ranking,
+            (Truncation::Radius(radius), BallotMut::Single(mut single)) => {
+                if single.choice().is_some_and(|c| !voter.is_within(c, radius)) {
+                    single.abstain();
+                }
+                return;
+            }
+            _ => return,
+        };
+        for c in 0..voter.num_candidates() {
+            let ranked = match self {
+                Truncation::Full => true,
+                Truncation::TopK(k) => ranking.rank(c).is_some_and(|rank| rank as usize <= k),
+                Truncation::Radius(radius) => voter.is_within(c, radius),
+            };
+            if !ranked {
+                ranking.unrank(c);
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+//! Voters who stay home.
+
+use std::str::FromStr;
+
+use crate::Voter;
+
+/// How many of the voters at each location turn out to vote.
+///
+/// Both reasons to stay home combine: the share of voters who turn out is the product of the
+/// shares each of them lets through. By default, everybody votes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Turnout {
+    /// Alienation: the share of voters who turn out halves for every `alienation` map units
+    /// between them and their closest candidate.
+    pub alienation: Option<f32>,
+    /// Indifference: voters whose two favorite candidates are `indifference` map units apart in
+    /// distance turn out half the time, and voters who like both the same stay home.
+    pub indifference: Option<f32>,
+}
+
+impl FromStr for Turnout {
+    type Err = String;
+
+    /// Parses `full`, or `alienation:<alienation>`, `indifference:<indifference>` or both,
+    /// separated by a comma.
+    fn from_str(s: &str) -> Result<Turnout, String> {
+        let mut turnout = Turnout::default();
+        if s == "full" {
+            return Ok(turnout);
+        }
+        for model in s.split(',') {
+            let (name, value) = model.split_once(':').ok_or_else(|| s.to_string())?;
+            let value = Some(value.parse().map_err(|_| s.to_string())?);
+            match name {
+                "alienation" => turnout.alienation = value,
+                "indifference" => turnout.indifference = value,
+                _ => return Err(s.to_string()),
+            }
+        }
+        Ok(turnout)
+    }
+}
+
+impl Turnout {
+    /// Whether every voter turns out.
+    pub fn is_full(&self) -> bool {
+        self.alienation.is_none() && self.indifference.is_none()
+    }
+
+    /// The share of voters like `voter` who turn out.
+    pub(crate) fn of(&self, voter: &Voter) -> f32 {
+        let mut closest = f32::INFINITY;
+        let mut second = f32::INFINITY;
+        for c in 0..voter.num_candidates() {
+            let distance = voter.distance(c);
+            if distance < closest {
+                second = closest;
+                closest = distance;
+            } else if distance < second {
+                second = distance;
+            }
+        }
+        let mut turnout = 1.0;
+        if let Some(alienation) = self.alienation {
+            turnout *= 0.5f32.powf(closest.max(0.0) / alienation);
+        }
+        if let Some(indifference) = self.indifference {
+            turnout *= 1.0 - 0.5f32.powf((second - closest) / indifference);
+        }
+        turnout
+    }
+}
@@ -0,0 +1,95 @@
+//! Candidate sets shared by the tests.
+
+use voting_map::Point;
+
+/// The candidates of the set with this name.
+pub fn get_candidates(name: &str) -> Vec<Point> {
+    if name == "equilateral" {
+        vec![
+            Point::new(0.5, 0.99),
+            Point::new(0.07, 0.25),
+            Point::new(0.93, 0.25),
+        ]
+    } else if name == "squeezed" {
+        vec![
+            Point::new(0.07, 0.17),
+            Point::new(0.49, 0.01),
+            Point::new(0.41, 0.02),
+        ]
+    } else if name == "split" {
+        vec![
+            Point::new(0.93, 0.49),
+            Point::new(0.79, 0.42),
+            Point::new(0.27, 0.45),
+        ]
+    } else if name == "nonmonotonic" {
+        vec![
+            Point::new(0.54, 0.47),
+            Point::new(0.77, 0.64),
+            Point::new(0.13, 0.10),
+        ]
+    } else if name == "square" {
+        vec![
+            Point::new(0.3, 0.3),
+            Point::new(0.3, 0.7),
+            Point::new(0.7, 0.7),
+            Point::new(0.7, 0.3),
+        ]
+    } else if name == "shattered" {
+        vec![
+            Point::new(0.12, 0.28),
+            Point::new(0.39, 0.28),
+            Point::new(0.97, 0.14),
+            Point::new(0.85, 0.70),
+        ]
+    } else if name == "disjoint" {
+        vec![
+            Point::new(0.24, 0.25),
+            Point::new(0.04, 0.64),
+            Point::new(0.85, 0.55),
+            Point::new(0.19, 0.62),
+        ]
+    } else if name == "nonmonotonicity" {
+        vec![
+            Point::new(0.4, 0.57),
+            Point::new(0.05, 0.62),
+            Point::new(0.91, 0.7),
+            Point::new(0.16, 0.54),
+        ]
+    } else if name == "ten" {
+        vec![
+            Point::new(0.15, 0.2),
+            Point::new(0.3, 0.85),
+            Point::new(0.5, 0.5),
+            Point::new(0.85, 0.3),
+            Point::new(0.7, 0.75),
+            Point::new(0.45, 0.1),
+            Point::new(0.05, 0.6),
+            Point::new(0.95, 0.9),
+            Point::new(0.6, 0.35),
+            Point::new(0.35, 0.55),
+        ]
+    } else if name == "kite" {
+        vec![
+            Point::new(0.2, 0.3),
+            Point::new(0.5, 0.55),
+            Point::new(0.8, 0.3),
+            Point::new(0.55, 0.9),
+        ]
+    } else if name == "skewed_kite" {
+        // No voter on the grid is exactly as far from two candidates, which would then share a
+        // rank.
+        vec![
+            Point::new(0.2, 0.3),
+            Point::new(0.5, 0.55),
+            Point::new(0.83, 0.28),
+            Point::new(0.55, 0.9),
+        ]
+    } else if name == "triangle" {
+        get_candidates("kite")[..3].to_vec()
+    } else if name == "skewed_triangle" {
+        get_candidates("skewed_kite")[..3].to_vec()
+    } else {
+        panic!("unknown candidate set: {}", name);
+    }
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{election, Convolution, Hare, Point, Settings, TIE_TOLERANCE};
+
+#[test]
+fn mirrored_candidates_tie_on_the_symmetry_axis() {
+    // The square of the screenshot tests is symmetric about the diagonal x + y = 1, which swaps
+    // the second and fourth candidates. Along it they tie exactly, so which of them wins only
+    // depends on how the ties are broken, and both convolutions have to find them.
+    let candidates: Vec<Point> = get_candidates("square")
+        .into_iter()
+        .map(|c| Point::new((c.x + 0.25) / 1.5, (1.25 - c.y) / 1.5))
+        .collect();
+    for convolution in [Convolution::Direct, Convolution::Fft] {
+        let settings = Settings {
+            convolution,
+            ..Settings::default()
+        };
+        let result = election(128, &candidates, &Hare, &settings).unwrap();
+        for x in 1..128 {
+            let (x, y) = (x, 128 - x);
+            let shares = result.shares_at(x, y);
+            let tolerance = shares[1].max(shares[3]) * TIE_TOLERANCE;
+            assert!(
+                (shares[1] - shares[3]).abs() <= tolerance,
+                "{:?} at ({}, {}): {:?}",
+                convolution,
+                x,
+                y,
+                shares
+            );
+            let outcome = result.outcome_at(x, y);
+            if outcome.winner == 1 || outcome.winner == 3 {
+                assert!(outcome.tied, "{:?} at ({}, {})", convolution, x, y);
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{
+    election, Borda, Cluster, Covariance, Electorate, Plurality, Point, Settings, Shape,
+};
+
+fn max_share_difference(a: &Settings, b: &Settings) -> f32 {
+    let a = election(48, &get_candidates("kite"), &Borda, a).unwrap();
+    let b = election(48, &get_candidates("kite"), &Borda, b).unwrap();
+    a.shares()
+        .iter()
+        .zip(b.shares())
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f32::max)
+}
+
+#[test]
+fn separable_matches_full_kernel() {
+    // A barely correlated cluster takes the 2D path, but should be close to the separable one.
+    for &shape in [Shape::Square, Shape::Laplace, Shape::Cauchy].iter() {
+        let with = |correlation| Settings {
+            electorate: Electorate::single(
+                shape,
+                Covariance {
+                    sigma_x: 0.15,
+                    sigma_y: 0.25,
+                    correlation,
+                },
+            ),
+            ..Settings::default()
+        };
+        let difference = max_share_difference(&with(0.0), &with(1e-6));
+        assert!(difference < 1e-3, "{:?} differs by {}", shape, difference);
+    }
+}
+
+#[test]
+fn correlation_has_the_same_sign_on_both_paths() {
+    // Correlated Gaussians use the sheared separable path, other correlated shapes the 2D path.
+    let with = |shape, correlation| Settings {
+        electorate: Electorate::single(
+            shape,
+            Covariance {
+                sigma_x: 0.2,
+                sigma_y: 0.1,
+                correlation,
+            },
+        ),
+        ..Settings::default()
+    };
+    let same = max_share_difference(&with(Shape::Gaussian, 0.7), &with(Shape::Laplace, 0.7));
+    let opposite = max_share_difference(&with(Shape::Gaussian, 0.7), &with(Shape::Laplace, -0.7));
+    assert!(same < opposite, "{} >= {}", same, opposite);
+}
+
+#[test]
+fn narrow_disc_is_voronoi() {
+    let settings = Settings {
+        electorate: Electorate::single(Shape::Disc, Covariance::isotropic(0.01)),
+        ..Settings::default()
+    };
+    let result = election(32, &get_candidates("kite"), &Plurality, &settings).unwrap();
+    for x in 0..32 {
+        for y in 0..32 {
+            let at = Point::new(x as f32 / 32.0, y as f32 / 32.0);
+            let distances: Vec<f32> = get_candidates("kite")
+                .iter()
+                .map(|c| (c.x - at.x).powi(2) + (c.y - at.y).powi(2))
+                .collect();
+            let winner = result.outcome_at(x, y).winner;
+            assert!(distances.iter().all(|&d| distances[winner] <= d + 1e-3));
+        }
+    }
+}
+
+#[test]
+fn heavy_tailed_clusters_keep_their_weight() {
+    // Each narrow cluster votes only for the candidate on its side, so the shares are the
+    // weights of the clusters, however much of each heavy tailed cluster lies in its tails.
+    let candidates = vec![Point::new(0.5, 0.05), Point::new(0.5, 0.95)];
+    let cluster = |shape, offset| Cluster {
+        weight: 1.0,
+        offset: Point::new(0.0, offset),
+        shape,
+        covariance: Covariance::isotropic(0.02),
+    };
+    let settings = Settings {
+        electorate: Electorate {
+            clusters: vec![cluster(Shape::Laplace, -0.3), cluster(Shape::Cauchy, 0.3)],
+        },
+        ..Settings::default()
+    };
+    let result = election(64, &candidates, &Plurality, &settings).unwrap();
+    let shares = result.shares_at(32, 32);
+    assert!((shares[0] - shares[1]).abs() < 1e-3, "{:?}", shares);
+}
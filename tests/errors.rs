@@ -0,0 +1,529 @@
+use voting_map::{
+    election, election_in_slice, parse_candidates, sample_election, Approval, BallotType, Cluster,
+    Covariance, ElectionError, Electorate, HiddenIssue, Integration, MethodRegistry, Metric,
+    Plurality, Point, Sampling, Settings, Shape, Slice, Strategy, Truncation, Turnout, Valence,
+    VoterModel, MAX_DRAWS, MAX_EXACT_CANDIDATES, MAX_HIDDEN_POSITIONS,
+};
+
+#[test]
+fn unknown_method() {
+    assert_eq!(
+        MethodRegistry::default().get("condorcet").err(),
+        Some(ElectionError::UnknownMethod("condorcet".to_string()))
+    );
+}
+
+#[test]
+fn method_index_out_of_range() {
+    let registry = MethodRegistry::default();
+    assert_eq!(
+        registry.get_index(registry.len()).err(),
+        Some(ElectionError::MethodIndexOutOfRange {
+            index: registry.len(),
+            count: registry.len()
+        })
+    );
+}
+
+#[test]
+fn odd_coordinates() {
+    assert_eq!(
+        parse_candidates(&[0.1, 0.2, 0.3]),
+        Err(ElectionError::OddCoordinates(3))
+    );
+}
+
+#[test]
+fn invalid_elections() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    assert_eq!(
+        election(0, &candidates, &Plurality, &Settings::default()),
+        Err(ElectionError::ZeroSize)
+    );
+    assert_eq!(
+        election(16, &[], &Plurality, &Settings::default()),
+        Err(ElectionError::NoCandidates)
+    );
+    assert_eq!(
+        election(
+            16,
+            &[Point::new(0.5, f32::NAN)],
+            &Plurality,
+            &Settings::default()
+        ),
+        Err(ElectionError::NonFiniteCoordinate(0))
+    );
+    assert_eq!(
+        election(
+            16,
+            &[Point::new(0.5, 0.5), Point::new(f32::NEG_INFINITY, 0.5)],
+            &Plurality,
+            &Settings::default()
+        ),
+        Err(ElectionError::NonFiniteCoordinate(1))
+    );
+    assert_eq!(
+        election(
+            16,
+            &[Point::new(0.5, 0.5); 65],
+            &Plurality,
+            &Settings::default()
+        ),
+        Err(ElectionError::TooManyCandidates { count: 65, max: 64 })
+    );
+}
+
+#[test]
+fn invalid_spread() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let with = |sigma, num_sigma| Settings {
+        electorate: Electorate::gaussian(Covariance::isotropic(sigma)),
+        num_sigma,
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(0.0, 3.0)),
+        Err(ElectionError::InvalidSigma(0.0))
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(f32::INFINITY, 3.0)),
+        Err(ElectionError::InvalidSigma(f32::INFINITY))
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(0.2, -1.0)),
+        Err(ElectionError::InvalidNumSigma(-1.0))
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(2.0, 3.0)),
+        Err(ElectionError::SpreadTooLarge {
+            spread: 6.0,
+            max: 4.0
+        })
+    );
+    assert!(election(16, &candidates, &Plurality, &with(0.001, 3.0)).is_ok());
+
+    let correlated = |correlation| Settings {
+        electorate: Electorate::gaussian(Covariance {
+            correlation,
+            ..Covariance::isotropic(0.2)
+        }),
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(16, &candidates, &Plurality, &correlated(1.0)),
+        Err(ElectionError::InvalidCorrelation(1.0))
+    );
+    assert!(election(16, &candidates, &Plurality, &correlated(-0.99)).is_ok());
+
+    // Correlated clusters are sheared along x, which reaches further than their widest axis.
+    let sheared = Settings {
+        electorate: Electorate::gaussian(Covariance {
+            correlation: 0.9,
+            ..Covariance::isotropic(1.0)
+        }),
+        num_sigma: 3.5,
+        ..Settings::default()
+    };
+    assert!(matches!(
+        election(16, &candidates, &Plurality, &sheared),
+        Err(ElectionError::SpreadTooLarge { spread, .. }) if spread > 4.6
+    ));
+}
+
+#[test]
+fn invalid_electorate() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let cluster = |weight, x| Cluster {
+        weight,
+        offset: Point::new(x, 0.0),
+        shape: Shape::Gaussian,
+        covariance: Covariance::isotropic(0.2),
+    };
+    let with = |clusters| Settings {
+        electorate: Electorate { clusters },
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(vec![])),
+        Err(ElectionError::EmptyElectorate)
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(vec![cluster(0.0, 0.0)])),
+        Err(ElectionError::EmptyElectorate)
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(vec![cluster(1.0, 0.0), cluster(-1.0, 0.0)])
+        ),
+        Err(ElectionError::InvalidCluster(1))
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(vec![cluster(1.0, f32::NAN)])
+        ),
+        Err(ElectionError::InvalidCluster(0))
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(vec![cluster(1.0, 3.5)])),
+        Err(ElectionError::SpreadTooLarge {
+            spread: 3.5 + 0.2 * 3.0,
+            max: 4.0
+        })
+    );
+    assert!(election(
+        16,
+        &candidates,
+        &Plurality,
+        &with(vec![cluster(1.0, -0.5), cluster(0.0, 0.5)])
+    )
+    .is_ok());
+}
+
+#[test]
+fn invalid_metric() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let with = |metric| Settings {
+        metric,
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(Metric::Minkowski { p: 0.5 })
+        ),
+        Err(ElectionError::InvalidMinkowskiOrder(0.5))
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(Metric::Minkowski { p: f32::INFINITY })
+        ),
+        Err(ElectionError::InvalidMinkowskiOrder(f32::INFINITY))
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(Metric::Salience { x: 1.0, y: 0.0 })
+        ),
+        Err(ElectionError::InvalidSalience { x: 1.0, y: 0.0 })
+    );
+    assert_eq!("minkowski:3".parse(), Ok(Metric::Minkowski { p: 3.0 }));
+    assert_eq!(
+        "salience:2,1".parse(),
+        Ok(Metric::Salience { x: 2.0, y: 1.0 })
+    );
+    assert!("manhattan".parse::<Metric>().is_err());
+}
+
+#[test]
+fn invalid_slice() {
+    let candidates = vec![vec![0.2, 0.2, 0.5], vec![0.8, 0.8, 0.5]];
+    let slice = Slice::through((0, 1), &[0.0, 0.0, 0.5], 0.2);
+    let run = |candidates: &[Vec<f32>], slice: &Slice| {
+        election_in_slice(16, candidates, slice, &Plurality, &Settings::default())
+    };
+    assert!(run(&candidates, &slice).is_ok());
+    assert_eq!(
+        run(
+            &candidates[..1],
+            &Slice {
+                axes: (1, 1),
+                ..slice.clone()
+            }
+        ),
+        Err(ElectionError::InvalidAxes {
+            x: 1,
+            y: 1,
+            dimensions: 3
+        })
+    );
+    assert_eq!(
+        run(
+            &candidates,
+            &Slice {
+                axes: (0, 3),
+                ..slice.clone()
+            }
+        ),
+        Err(ElectionError::InvalidAxes {
+            x: 0,
+            y: 3,
+            dimensions: 3
+        })
+    );
+    assert_eq!(
+        run(&[vec![0.2, 0.2, 0.5], vec![0.8, 0.8]], &slice),
+        Err(ElectionError::DimensionMismatch {
+            candidate: 1,
+            count: 2,
+            dimensions: 3
+        })
+    );
+    assert_eq!(
+        run(&[vec![0.2, 0.2, 0.5], vec![0.8, 0.8, f32::NAN]], &slice),
+        Err(ElectionError::NonFiniteCoordinate(1))
+    );
+    let hidden = HiddenIssue {
+        center: 0.5,
+        sigma: 0.0,
+    };
+    assert_eq!(
+        run(
+            &candidates,
+            &Slice {
+                hidden: vec![hidden],
+                ..slice.clone()
+            }
+        ),
+        Err(ElectionError::InvalidHiddenIssue(0))
+    );
+    assert_eq!(
+        run(
+            &candidates,
+            &Slice {
+                samples: 0,
+                ..slice.clone()
+            }
+        ),
+        Err(ElectionError::ZeroSamples)
+    );
+    let deep = Slice::through((0, 1), &[0.5; 5], 0.2);
+    assert_eq!(
+        run(&[vec![0.5; 5]], &deep),
+        Err(ElectionError::TooManyHiddenPositions {
+            count: 729,
+            max: MAX_HIDDEN_POSITIONS
+        })
+    );
+}
+
+#[test]
+fn invalid_valence() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let with = |valence| Settings {
+        valence,
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(vec![Valence::additive(0.1)])
+        ),
+        Err(ElectionError::ValenceMismatch {
+            count: 1,
+            num_candidates: 2
+        })
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(vec![Valence::default(), Valence::multiplicative(0.0)])
+        ),
+        Err(ElectionError::InvalidValence(1))
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(vec![Valence::additive(f32::NAN), Valence::default()])
+        ),
+        Err(ElectionError::InvalidValence(0))
+    );
+}
+
+#[test]
+fn invalid_voter_model() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let logit = |temperature, draws| Settings {
+        voter_model: VoterModel::Logit { temperature, draws },
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(16, &candidates, &Plurality, &logit(0.0, 16)),
+        Err(ElectionError::InvalidTemperature(0.0))
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &logit(0.1, 0)),
+        Err(ElectionError::InvalidDraws {
+            draws: 0,
+            max: MAX_DRAWS
+        })
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &logit(0.1, MAX_DRAWS + 1)),
+        Err(ElectionError::InvalidDraws {
+            draws: MAX_DRAWS + 1,
+            max: MAX_DRAWS
+        })
+    );
+}
+
+#[test]
+fn invalid_strategy() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let with = |strategy, strategic_share| Settings {
+        strategy,
+        strategic_share,
+        ..Settings::default()
+    };
+    let approval = Approval::default();
+    assert_eq!(
+        election(16, &candidates, &approval, &with(Strategy::Bury, 1.0)),
+        Err(ElectionError::StrategyMismatch {
+            strategy: Strategy::Bury,
+            ballot_type: BallotType::Approval
+        })
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(Strategy::ApproveFrontrunner, 1.0)
+        ),
+        Err(ElectionError::StrategyMismatch {
+            strategy: Strategy::ApproveFrontrunner,
+            ballot_type: BallotType::Single
+        })
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(Strategy::Bury, 1.0)),
+        Err(ElectionError::StrategyMismatch {
+            strategy: Strategy::Bury,
+            ballot_type: BallotType::Single
+        })
+    );
+    assert_eq!(
+        election(
+            16,
+            &candidates,
+            &Plurality,
+            &with(Strategy::Compromise, 1.5)
+        ),
+        Err(ElectionError::InvalidStrategicShare(1.5))
+    );
+}
+
+#[test]
+fn invalid_truncation() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    for &truncation in [Truncation::TopK(0), Truncation::Radius(0.0)].iter() {
+        let settings = Settings {
+            truncation,
+            ..Settings::default()
+        };
+        assert_eq!(
+            election(16, &candidates, &Plurality, &settings),
+            Err(ElectionError::InvalidTruncation(truncation))
+        );
+    }
+    let settings = Settings {
+        truncation: Truncation::Radius(f32::NAN),
+        ..Settings::default()
+    };
+    assert!(matches!(
+        election(16, &candidates, &Plurality, &settings),
+        Err(ElectionError::InvalidTruncation(_))
+    ));
+}
+
+#[test]
+fn invalid_turnout() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let with = |alienation, indifference| Settings {
+        turnout: Turnout {
+            alienation,
+            indifference,
+        },
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(Some(0.0), None)),
+        Err(ElectionError::InvalidAlienation(0.0))
+    );
+    assert_eq!(
+        election(16, &candidates, &Plurality, &with(None, Some(-0.1))),
+        Err(ElectionError::InvalidIndifference(-0.1))
+    );
+}
+
+#[test]
+fn empty_sample() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let settings = Settings::default();
+    let run = |num_voters, repeats| {
+        let sampling = Sampling {
+            num_voters,
+            repeats,
+            seed: 0,
+        };
+        sample_election(16, &candidates, &Plurality, &settings, &sampling)
+    };
+    assert_eq!(run(0, 4), Err(ElectionError::ZeroVoters));
+    assert_eq!(run(100, 0), Err(ElectionError::ZeroRepeats));
+}
+
+#[test]
+fn exact_unsupported() {
+    let candidates = vec![Point::new(0.2, 0.2), Point::new(0.8, 0.8)];
+    let exact = Settings {
+        integration: Integration::Exact,
+        ..Settings::default()
+    };
+    assert_eq!(
+        election(16, &candidates, &Approval::default(), &exact),
+        Err(ElectionError::ExactUnsupported("approval ballots"))
+    );
+    let cityblock = Settings {
+        metric: Metric::CityBlock,
+        ..exact.clone()
+    };
+    assert_eq!(
+        election(16, &candidates, &Plurality, &cityblock),
+        Err(ElectionError::ExactUnsupported("non-Euclidean metrics"))
+    );
+    let disc = Settings {
+        electorate: Electorate::single(Shape::Disc, Covariance::isotropic(0.2)),
+        ..exact.clone()
+    };
+    assert_eq!(
+        election(16, &candidates, &Plurality, &disc),
+        Err(ElectionError::ExactUnsupported("non-Gaussian clusters"))
+    );
+    let sampling = Sampling {
+        num_voters: 100,
+        repeats: 4,
+        seed: 0,
+    };
+    assert_eq!(
+        sample_election(16, &candidates, &Plurality, &exact, &sampling),
+        Err(ElectionError::ExactUnsupported("sampled electorates"))
+    );
+    let crowded: Vec<Point> = (0..=MAX_EXACT_CANDIDATES)
+        .map(|i| Point::new(i as f32 / MAX_EXACT_CANDIDATES as f32, 0.5))
+        .collect();
+    assert_eq!(
+        election(16, &crowded, &Plurality, &exact),
+        Err(ElectionError::TooManyExactCandidates {
+            count: MAX_EXACT_CANDIDATES + 1,
+            max: MAX_EXACT_CANDIDATES
+        })
+    );
+}
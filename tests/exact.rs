@@ -0,0 +1,127 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{
+    election, explain, Borda, Covariance, ElectionMethod, Electorate, Hare, Integration, Minimax,
+    Plurality, Point, Settings,
+};
+
+fn exact(electorate: Electorate) -> Settings {
+    Settings {
+        electorate,
+        integration: Integration::Exact,
+        ..Settings::default()
+    }
+}
+
+fn narrow() -> Electorate {
+    Electorate::gaussian(Covariance::isotropic(0.1))
+}
+
+#[test]
+fn shares_are_gaussian_masses() {
+    let candidates = vec![Point::new(0.3, 0.5), Point::new(0.7, 0.5)];
+    let result = election(10, &candidates, &Plurality, &exact(narrow())).unwrap();
+    // Voters around (0.4, 0.5) prefer the first candidate up to one standard deviation to the
+    // right of the center, which leaves out one tail of the normal distribution.
+    let shares = result.shares_at(4, 5);
+    assert!((shares[0] - 0.841_344_7).abs() < 1e-5, "{:?}", shares);
+    assert!((shares[1] - 0.158_655_3).abs() < 1e-5, "{:?}", shares);
+    let shares = result.shares_at(5, 2);
+    assert!((shares[0] - 0.5).abs() < 1e-6, "{:?}", shares);
+}
+
+#[test]
+fn shares_match_a_fine_grid() {
+    let electorates = [
+        Electorate::gaussian(Covariance::isotropic(0.15)),
+        Electorate::gaussian(Covariance {
+            sigma_x: 0.2,
+            sigma_y: 0.1,
+            correlation: 0.6,
+        }),
+        Electorate::bimodal(Covariance::isotropic(0.1), Point::new(0.3, -0.1)),
+    ];
+    let methods: [&dyn ElectionMethod; 3] = [&Plurality, &Borda, &Minimax];
+    // The grid converges to the exact shares as the map grows. Clusters of the bimodal electorate
+    // are a whole number of pixels away from the point.
+    for electorate in electorates {
+        let grid = Settings {
+            electorate: electorate.clone(),
+            num_sigma: 5.0,
+            ..Settings::default()
+        };
+        for method in methods {
+            let expected = election(80, &get_candidates("skewed_kite"), method, &grid).unwrap();
+            let result = election(
+                80,
+                &get_candidates("skewed_kite"),
+                method,
+                &exact(electorate.clone()),
+            )
+            .unwrap();
+            for (a, b) in result.shares().iter().zip(expected.shares()) {
+                assert!((a - b).abs() < 5e-3, "{}: {} vs {}", method.id(), a, b);
+            }
+        }
+    }
+}
+
+#[test]
+fn shares_dont_depend_on_the_map() {
+    let settings = exact(narrow());
+    let small = election(16, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+    let large = election(64, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+    let truncated = Settings {
+        num_sigma: 1.0,
+        ..settings
+    };
+    let truncated = election(16, &get_candidates("skewed_kite"), &Hare, &truncated).unwrap();
+    for &(x, y) in [(8, 8), (3, 12), (15, 0)].iter() {
+        let shares = small.shares_at(x, y);
+        for (a, b) in shares.iter().zip(large.shares_at(x * 4, y * 4)) {
+            assert!((a - b).abs() < 1e-6, "{} vs {}", a, b);
+        }
+        assert_eq!(shares, truncated.shares_at(x, y));
+    }
+}
+
+#[test]
+fn symmetric_candidates_tie() {
+    let candidates = vec![
+        Point::new(0.25, 0.3),
+        Point::new(0.75, 0.3),
+        Point::new(0.5, 0.8),
+    ];
+    let result = election(16, &candidates, &Borda, &exact(narrow())).unwrap();
+    for y in 0..16 {
+        let shares = result.shares_at(8, y);
+        assert!((shares[0] - shares[1]).abs() < 1e-6, "{:?}", shares);
+    }
+}
+
+#[test]
+fn explanations_match_the_map() {
+    let settings = exact(narrow());
+    let result = election(32, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+    for &(x, y) in [(16, 16), (5, 20), (28, 3)].iter() {
+        let explanation =
+            explain(32, x, y, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+        let outcome = result.outcome_at(x as usize, y as usize);
+        assert_eq!(explanation.outcome, outcome);
+        for (a, b) in explanation.rounds[0]
+            .shares
+            .iter()
+            .zip(result.shares_at(x as usize, y as usize))
+        {
+            assert!((a - b).abs() < 1e-6, "{} vs {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn parse_integration() {
+    assert_eq!("grid".parse(), Ok(Integration::Grid));
+    assert_eq!("exact".parse(), Ok(Integration::Exact));
+    assert!("voronoi".parse::<Integration>().is_err());
+}
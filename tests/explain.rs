@@ -0,0 +1,84 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{election, explain, Hare, Minimax, Plurality, Point, Settings};
+
+#[test]
+fn plurality_single_round() {
+    let settings = Settings::default();
+    let result = election(32, &get_candidates("kite"), &Plurality, &settings).unwrap();
+    let explanation = explain(32, 5, 20, &get_candidates("kite"), &Plurality, &settings).unwrap();
+    assert_eq!(explanation.outcome, result.outcome_at(5, 20));
+    assert_eq!(explanation.rounds.len(), 1);
+    assert_eq!(explanation.rounds[0].eliminated, None);
+    assert!(explanation.rounds[0].transfers.is_empty());
+    assert!(explanation.pairwise.is_none());
+}
+
+#[test]
+fn hare_matches_map() {
+    let settings = Settings::default();
+    let result = election(32, &get_candidates("kite"), &Hare, &settings).unwrap();
+    for &(x, y) in [(3, 4), (16, 16), (16, 30), (28, 9), (10, 25)].iter() {
+        let explanation = explain(32, x, y, &get_candidates("kite"), &Hare, &settings).unwrap();
+        assert_eq!(
+            explanation.outcome,
+            result.outcome_at(x as usize, y as usize)
+        );
+        assert_eq!(
+            explanation.rounds.len(),
+            result.num_rounds[x as usize * 32 + y as usize]
+        );
+
+        let mut eliminated = vec![];
+        for (i, round) in explanation.rounds.iter().enumerate() {
+            for c in eliminated.iter() {
+                assert_eq!(round.shares[*c], 0.0);
+            }
+            match round.eliminated {
+                Some(loser) => {
+                    // The loser's votes all move to other candidates.
+                    assert!(round.transfers[loser] < 0.0);
+                    assert!(round.transfers.iter().sum::<f32>().abs() < 1e-4);
+                    eliminated.push(loser);
+                }
+                None => assert_eq!(i, explanation.rounds.len() - 1),
+            }
+        }
+
+        let last = &explanation.rounds.last().unwrap().shares;
+        assert!(last[explanation.outcome.winner] >= 0.5);
+    }
+}
+
+#[test]
+fn hare_eliminates_until_majority() {
+    // The candidate in the middle is squeezed out by the two around it.
+    let candidates = vec![
+        Point::new(0.38, 0.5),
+        Point::new(0.5, 0.5),
+        Point::new(0.6, 0.5),
+    ];
+    let explanation = explain(32, 16, 16, &candidates, &Hare, &Settings::default()).unwrap();
+    assert_eq!(explanation.rounds.len(), 2);
+    assert_eq!(explanation.rounds[0].eliminated, Some(1));
+    assert_eq!(explanation.rounds[1].shares[1], 0.0);
+}
+
+#[test]
+fn minimax_pairwise_matrix() {
+    let settings = Settings::default();
+    let explanation = explain(32, 16, 16, &get_candidates("kite"), &Minimax, &settings).unwrap();
+    let pairwise = explanation.pairwise.unwrap();
+    assert_eq!(pairwise.len(), 4);
+    for (a, row) in pairwise.iter().enumerate() {
+        assert_eq!(row[a], 0.0);
+        for (b, support) in row.iter().enumerate().filter(|&(b, _)| b != a) {
+            assert!((support + pairwise[b][a] - 1.0).abs() < 1e-4);
+        }
+    }
+
+    // The center candidate beats everyone at the center of the map.
+    assert_eq!(explanation.outcome.winner, 1);
+    assert!((0..4).filter(|&b| b != 1).all(|b| pairwise[1][b] > 0.5));
+}
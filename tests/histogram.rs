@@ -0,0 +1,35 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{election, explain, Borda, ElectionMethod, Hare, Minimax, Plurality, Settings};
+
+#[test]
+fn methods_agree_on_two_candidates() {
+    // With two candidates, every method counts the same voters for each of them.
+    let candidates = &get_candidates("skewed_kite")[..2];
+    let settings = Settings::default();
+    let expected = election(24, candidates, &Plurality, &settings).unwrap();
+    for method in [&Borda as &dyn ElectionMethod, &Hare, &Minimax] {
+        let result = election(24, candidates, method, &settings).unwrap();
+        for (a, b) in result.shares().iter().zip(expected.shares()) {
+            assert!((a - b).abs() < 1e-6, "{}: {} vs {}", method.id(), a, b);
+        }
+    }
+}
+
+#[test]
+fn hare_rounds_match_explanations() {
+    let settings = Settings::default();
+    let result = election(24, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+    for &(x, y) in [(2, 3), (12, 12), (12, 22), (21, 7), (7, 19)].iter() {
+        let explanation =
+            explain(24, x, y, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+        let point = (x * 24 + y) as usize;
+        for (round, explained) in result.rounds.iter().zip(&explanation.rounds) {
+            let shares = &round[point * 4..point * 4 + 4];
+            for (a, b) in shares.iter().zip(&explained.shares) {
+                assert!((a - b).abs() < 1e-6, "({}, {}): {} vs {}", x, y, a, b);
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{
+    election, explain, Covariance, Electorate, Hare, Plurality, Point, Settings, VoterModel,
+};
+
+#[test]
+fn first_choices_follow_logit() {
+    let temperature = 0.1;
+    let settings = Settings {
+        voter_model: VoterModel::Logit {
+            temperature,
+            draws: 256,
+        },
+        electorate: Electorate::gaussian(Covariance::isotropic(0.01)),
+        ..Settings::default()
+    };
+    let size = 64;
+    let (x, y) = (24, 30);
+    let explanation = explain(
+        size,
+        x,
+        y,
+        &get_candidates("triangle"),
+        &Plurality,
+        &settings,
+    )
+    .unwrap();
+
+    let at = Point::new(x as f32 / size as f32, y as f32 / size as f32);
+    let weights: Vec<f32> = get_candidates("triangle")
+        .iter()
+        .map(|c| (-((c.x - at.x).powi(2) + (c.y - at.y).powi(2)).sqrt() / temperature).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+    for (share, weight) in explanation.rounds[0].shares.iter().zip(&weights) {
+        assert!(
+            (share - weight / total).abs() < 0.03,
+            "{:?} vs {:?}",
+            explanation.rounds[0].shares,
+            weights
+        );
+    }
+}
+
+#[test]
+fn cold_voters_are_deterministic() {
+    let deterministic =
+        election(32, &get_candidates("triangle"), &Hare, &Settings::default()).unwrap();
+    let settings = Settings {
+        voter_model: VoterModel::Logit {
+            temperature: 1e-6,
+            draws: 4,
+        },
+        ..Settings::default()
+    };
+    let cold = election(32, &get_candidates("triangle"), &Hare, &settings).unwrap();
+    let shares = deterministic.shares().iter().zip(cold.shares());
+    let difference = shares.map(|(a, b)| (a - b).abs()).fold(0.0, f32::max);
+    assert!(difference < 1e-3, "differs by {}", difference);
+}
+
+#[test]
+fn noise_shrinks_the_lead() {
+    // The further voters are from deterministic, the closer every election gets.
+    let margin = |voter_model| {
+        let settings = Settings {
+            voter_model,
+            ..Settings::default()
+        };
+        let result = election(32, &get_candidates("triangle"), &Plurality, &settings).unwrap();
+        result.margins().iter().sum::<f32>()
+    };
+    let deterministic = margin(VoterModel::Deterministic);
+    let warm = margin(VoterModel::Logit {
+        temperature: 0.05,
+        draws: 16,
+    });
+    let hot = margin(VoterModel::Logit {
+        temperature: 0.5,
+        draws: 16,
+    });
+    assert!(
+        deterministic > warm && warm > hot,
+        "{} {} {}",
+        deterministic,
+        warm,
+        hot
+    );
+}
+
+#[test]
+fn noise_does_not_depend_on_the_map_size() {
+    // A narrow electorate only counts the ballots cast right at each point, which are the same
+    // wherever the point falls on the grid.
+    let settings = Settings {
+        voter_model: VoterModel::Logit {
+            temperature: 0.1,
+            draws: 64,
+        },
+        electorate: Electorate::gaussian(Covariance::isotropic(0.0005)),
+        ..Settings::default()
+    };
+    let shares = |size, x, y| {
+        let explanation = explain(size, x, y, &get_candidates("triangle"), &Hare, &settings);
+        explanation.unwrap().rounds[0].shares.clone()
+    };
+    for &(x, y) in [(10, 21), (16, 16), (3, 30)].iter() {
+        assert_eq!(shares(32, x, y), shares(128, 4 * x, 4 * y));
+    }
+}
+
+#[test]
+fn parse_voter_model() {
+    assert_eq!("deterministic".parse(), Ok(VoterModel::Deterministic));
+    assert_eq!(
+        "logit:0.1".parse(),
+        Ok(VoterModel::Logit {
+            temperature: 0.1,
+            draws: 16
+        })
+    );
+    assert_eq!(
+        "logit:0.1,64".parse(),
+        Ok(VoterModel::Logit {
+            temperature: 0.1,
+            draws: 64
+        })
+    );
+    assert!("probit:0.1".parse::<VoterModel>().is_err());
+}
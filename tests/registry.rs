@@ -0,0 +1,58 @@
+use voting_map::{
+    election_method_ballot_type, election_method_id, election_method_parameters,
+    num_election_methods, ElectionError, MethodRegistry,
+};
+
+#[test]
+fn web_page_lists_every_method() {
+    let ids: Vec<String> = (0..num_election_methods())
+        .map(|i| election_method_id(i).unwrap())
+        .collect();
+    assert_eq!(ids, ["plurality", "approval", "borda", "hare", "minimax"]);
+    let ballot_types: Vec<String> = (0..num_election_methods())
+        .map(|i| election_method_ballot_type(i).unwrap())
+        .collect();
+    assert_eq!(
+        ballot_types,
+        ["single", "approval", "ranking", "ranking", "ranking"]
+    );
+
+    let parameters: serde_json::Value =
+        serde_json::from_str(&election_method_parameters(1).unwrap()).unwrap();
+    assert_eq!(parameters[0]["id"], "radius");
+    assert_eq!(parameters[0]["name"], "Approval radius");
+    assert_eq!(parameters[0]["value"], 1.0);
+    assert_eq!(election_method_parameters(0).unwrap(), "[]");
+}
+
+#[test]
+fn configure_sets_parameters_in_order() {
+    let mut registry = MethodRegistry::default();
+    let approval = registry.configure("approval", &[0.5]).unwrap();
+    assert_eq!(approval.parameters()[0].value, 0.5);
+    assert!(registry.configure("borda", &[]).is_ok());
+
+    assert_eq!(
+        registry.configure("approval", &[]).err(),
+        Some(ElectionError::ParameterCount {
+            method: "approval",
+            count: 0,
+            expected: 1,
+        })
+    );
+    assert_eq!(
+        registry.configure("approval", &[-1.0]).err(),
+        Some(ElectionError::InvalidParameter {
+            id: "radius",
+            value: -1.0,
+        })
+    );
+    assert_eq!(
+        registry.configure("plurality", &[1.0]).err(),
+        Some(ElectionError::ParameterCount {
+            method: "plurality",
+            count: 1,
+            expected: 0,
+        })
+    );
+}
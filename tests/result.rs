@@ -0,0 +1,43 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{election, Hare, Plurality, Settings};
+
+#[test]
+fn plurality_shares() {
+    let result = election(
+        32,
+        &get_candidates("kite"),
+        &Plurality,
+        &Settings::default(),
+    )
+    .unwrap();
+    assert_eq!(result.size, 32);
+    assert_eq!(result.num_candidates, 4);
+    assert_eq!(result.method, "plurality");
+    assert_eq!(result.rounds.len(), 1);
+    assert_eq!(result.shares().len(), 32 * 32 * 4);
+
+    for x in 0..32 {
+        for y in 0..32 {
+            let shares = result.shares_at(x, y);
+            assert!((shares.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+            let winner = result.outcome_at(x, y).winner;
+            assert!(shares.iter().all(|&s| s <= shares[winner]));
+        }
+    }
+}
+
+#[test]
+fn hare_rounds() {
+    let result = election(32, &get_candidates("kite"), &Hare, &Settings::default()).unwrap();
+    let max_rounds = *result.num_rounds.iter().max().unwrap();
+    assert!(max_rounds > 1);
+    assert_eq!(result.rounds.len(), max_rounds);
+
+    // The winner has a majority in the final round.
+    let last = result.rounds.last().unwrap();
+    for (i, outcome) in result.outcomes.iter().enumerate() {
+        assert!(last[i * 4 + outcome.winner] >= 0.5);
+    }
+}
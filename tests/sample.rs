@@ -0,0 +1,117 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{
+    election, sample_election, Borda, ElectionMethod, Hare, Plurality, Sampling, Settings, Strategy,
+};
+
+fn sampling(num_voters: usize, seed: u64) -> Sampling {
+    Sampling {
+        num_voters,
+        repeats: 8,
+        seed,
+    }
+}
+
+#[test]
+fn large_electorates_elect_the_same_winners() {
+    let settings = Settings::default();
+    for method in [&Plurality as &dyn ElectionMethod, &Hare, &Borda] {
+        let exact = election(16, &get_candidates("skewed_triangle"), method, &settings).unwrap();
+        let sampled = sample_election(
+            16,
+            &get_candidates("skewed_triangle"),
+            method,
+            &settings,
+            &sampling(100_000, 0),
+        )
+        .unwrap();
+        let margins = exact.margins();
+        let winners = exact.winners().zip(sampled.winners());
+        let uncertainty = sampled.uncertainty();
+        for (point, (expected, winner)) in winners.enumerate() {
+            // Close races may still be won by the runner-up of the whole electorate.
+            if margins[point] > 0.02 {
+                assert_eq!(winner, expected, "{} at point {}", method.id(), point);
+                assert_eq!(
+                    uncertainty[point],
+                    0.0,
+                    "{} at point {}",
+                    method.id(),
+                    point
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn frequencies_add_up_to_one() {
+    let settings = Settings::default();
+    let sampled = sample_election(
+        16,
+        &get_candidates("skewed_triangle"),
+        &Hare,
+        &settings,
+        &sampling(50, 3),
+    )
+    .unwrap();
+    assert_eq!(sampled.frequencies.len(), 16 * 16 * 3);
+    for x in 0..16 {
+        for y in 0..16 {
+            let total: f32 = sampled.frequencies_at(x, y).iter().sum();
+            assert!((total - 1.0).abs() < 1e-5, "{} at ({}, {})", total, x, y);
+        }
+    }
+    assert!(sampled
+        .uncertainty()
+        .iter()
+        .all(|&u| (0.0..=1.0).contains(&u)));
+    // A handful of voters doesn't always agree with the whole electorate.
+    assert!(sampled.uncertainty().iter().any(|&u| u > 0.0));
+}
+
+#[test]
+fn seeds_pick_the_voters() {
+    let settings = Settings::default();
+    let run = |seed| {
+        sample_election(
+            16,
+            &get_candidates("skewed_triangle"),
+            &Plurality,
+            &settings,
+            &sampling(20, seed),
+        )
+    };
+    assert_eq!(run(7).unwrap(), run(7).unwrap());
+    assert_ne!(run(7).unwrap(), run(8).unwrap());
+}
+
+#[test]
+fn sampled_voters_can_vote_strategically() {
+    let settings = Settings {
+        strategy: Strategy::Compromise,
+        ..Settings::default()
+    };
+    let exact = election(
+        16,
+        &get_candidates("skewed_triangle"),
+        &Plurality,
+        &settings,
+    )
+    .unwrap();
+    let sampled = sample_election(
+        16,
+        &get_candidates("skewed_triangle"),
+        &Plurality,
+        &settings,
+        &sampling(100_000, 1),
+    )
+    .unwrap();
+    let margins = exact.margins();
+    for (point, (expected, winner)) in exact.winners().zip(sampled.winners()).enumerate() {
+        if margins[point] > 0.02 {
+            assert_eq!(winner, expected, "point {}", point);
+        }
+    }
+}
@@ -1,10 +1,18 @@
+mod common;
+
+use common::get_candidates;
 use image::GenericImageView;
-use voting_map::{election, Point, CANDIDATE_COLORS};
+use voting_map::{
+    draw, draw_sampled, election, election_in_slice, palette, sample_election, Cluster, Color,
+    Convolution, Covariance, Electorate, HiddenIssue, Integration, MethodRegistry, Metric, Point,
+    Sampling, Settings, Shading, Shape, Slice, Strategy, TieBreak, Truncation, Turnout, Valence,
+    VoterModel,
+};
 
 fn assert_image(name: &str, candidate: &image::DynamicImage) {
     std::fs::create_dir_all("test_output").expect("failed to create test_output");
     let candidate_path = format!("test_output/{}.png", name);
-    std::fs::remove_file(&candidate_path);
+    let _ = std::fs::remove_file(&candidate_path);
     candidate
         .save(&candidate_path)
         .expect("failed to write candidate image");
@@ -46,81 +54,85 @@ fn assert_image(name: &str, candidate: &image::DynamicImage) {
     panic!("Image differs in {} pixels", num_diffs);
 }
 
-fn get_candidates(name: &str) -> Vec<Point> {
-    if name == "equilateral" {
-        vec![
-            Point::new(0.5, 0.99),
-            Point::new(0.07, 0.25),
-            Point::new(0.93, 0.25),
-        ]
-    } else if name == "squeezed" {
-        vec![
-            Point::new(0.07, 0.17),
-            Point::new(0.49, 0.01),
-            Point::new(0.41, 0.02),
-        ]
-    } else if name == "split" {
-        vec![
-            Point::new(0.93, 0.49),
-            Point::new(0.79, 0.42),
-            Point::new(0.27, 0.45),
-        ]
-    } else if name == "nonmonotonic" {
-        vec![
-            Point::new(0.54, 0.47),
-            Point::new(0.77, 0.64),
-            Point::new(0.13, 0.10),
-        ]
-    } else if name == "square" {
-        vec![
-            Point::new(0.3, 0.3),
-            Point::new(0.3, 0.7),
-            Point::new(0.7, 0.7),
-            Point::new(0.7, 0.3),
-        ]
-    } else if name == "shattered" {
-        vec![
-            Point::new(0.12, 0.28),
-            Point::new(0.39, 0.28),
-            Point::new(0.97, 0.14),
-            Point::new(0.85, 0.70),
-        ]
-    } else if name == "disjoint" {
-        vec![
-            Point::new(0.24, 0.25),
-            Point::new(0.04, 0.64),
-            Point::new(0.85, 0.55),
-            Point::new(0.19, 0.62),
-        ]
-    } else if name == "nonmonotonicity" {
-        vec![
-            Point::new(0.4, 0.57),
-            Point::new(0.05, 0.62),
-            Point::new(0.91, 0.7),
-            Point::new(0.16, 0.54),
-        ]
-    } else {
-        panic!("unknown candidate set: {}", name);
-    }
-}
-
-fn assert_election(method: &str, candidate_name: &str, size: u32) {
-    let candidates = get_candidates(candidate_name);
+fn transform(candidates: Vec<Point>) -> Vec<Point> {
     let mut tranformed = vec![];
     for c in candidates {
         // Scale to [-0.25, 1.25] coordinates and flip y-axis to match http://zesty.ca/voting/sim/
         tranformed.push(Point::new((c.x + 0.25) / 1.5, (1.0 - c.y + 0.25) / 1.5));
     }
-    let winners = election(size as i32, &tranformed, method);
+    tranformed
+}
+
+fn assert_election(method: &str, candidate_name: &str, size: u32) {
+    // Both ways of convolving the ballots must draw the same map, pixel for pixel.
+    for convolution in [Convolution::Direct, Convolution::Fft] {
+        let settings = Settings {
+            convolution,
+            ..Settings::default()
+        };
+        let name = format!("{}_{}", method, candidate_name);
+        assert_election_with(&name, method, candidate_name, size, &settings);
+    }
+}
+
+/// Compares the winners of `method` under `settings` with the golden image `name`.
+fn assert_election_with(
+    name: &str,
+    method: &str,
+    candidate_name: &str,
+    size: u32,
+    settings: &Settings,
+) {
+    let got = draw_winners(method, candidate_name, size, settings, false);
+    assert_image(name, &got);
+}
+
+fn draw_winners(
+    method: &str,
+    candidate_name: &str,
+    size: u32,
+    settings: &Settings,
+    highlight_ties: bool,
+) -> image::DynamicImage {
+    let tranformed = transform(get_candidates(candidate_name));
+    let registry = MethodRegistry::default();
+    let result = election(
+        size as i32,
+        &tranformed,
+        registry.get(method).unwrap(),
+        settings,
+    )
+    .unwrap();
 
+    let colors = palette(tranformed.len());
     let got = image::ImageBuffer::from_fn(size, size, |x, y| {
-        let c = CANDIDATE_COLORS[winners[(x * size + y) as usize] as usize];
+        let outcome = result.outcome_at(x as usize, y as usize);
+        let c = if outcome.tied && highlight_ties {
+            Color::TIE
+        } else {
+            colors[outcome.winner]
+        };
         image::Rgb([c.r, c.g, c.b])
     });
+    image::DynamicImage::ImageRgb8(got)
+}
 
+fn assert_margins(method: &str, candidate_name: &str, size: u32) {
+    let candidates = transform(get_candidates(candidate_name));
+    let registry = MethodRegistry::default();
+    let result = election(
+        size as i32,
+        &candidates,
+        registry.get(method).unwrap(),
+        &Settings::default(),
+    )
+    .unwrap();
+
+    let pixels = draw(&result, Shading::Margin, false);
+    let got = image::RgbaImage::from_raw(size, size, pixels).unwrap();
     assert_image(
-        &format!("{}_{}", method, candidate_name),
-        &image::DynamicImage::ImageRgb8(got),
+        &format!("{}_{}_margin", method, candidate_name),
+        &image::DynamicImage::ImageRgba8(got),
     );
 }
 
@@ -144,6 +156,11 @@ fn plurality_nonmonotonic() {
     assert_election("plurality", "nonmonotonic", 256);
 }
 
+#[test]
+fn plurality_ten() {
+    assert_election("plurality", "ten", 256);
+}
+
 #[test]
 fn borda_equilateral() {
     assert_election("borda", "equilateral", 256);
@@ -164,6 +181,11 @@ fn borda_nonmonotonic() {
     assert_election("borda", "nonmonotonic", 256);
 }
 
+#[test]
+fn borda_ten() {
+    assert_election("borda", "ten", 256);
+}
+
 #[test]
 fn approval_equilateral() {
     assert_election("approval", "equilateral", 256);
@@ -184,6 +206,11 @@ fn approval_nonmonotonic() {
     assert_election("approval", "nonmonotonic", 256);
 }
 
+#[test]
+fn approval_ten() {
+    assert_election("approval", "ten", 256);
+}
+
 #[test]
 fn hare_equilateral() {
     assert_election("hare", "equilateral", 128);
@@ -223,3 +250,673 @@ fn hare_disjoint() {
 fn hare_nonmonotonicity() {
     assert_election("hare", "nonmonotonicity", 128);
 }
+
+#[test]
+fn hare_ten() {
+    assert_election("hare", "ten", 64);
+}
+
+#[test]
+fn minimax_equilateral() {
+    assert_election("minimax", "equilateral", 128);
+}
+
+#[test]
+fn minimax_nonmonotonic() {
+    assert_election("minimax", "nonmonotonic", 128);
+}
+
+#[test]
+fn minimax_shattered() {
+    assert_election("minimax", "shattered", 128);
+}
+
+#[test]
+fn hare_square_ties() {
+    let got = draw_winners("hare", "square", 128, &Settings::default(), true);
+    assert_image("hare_square_ties", &got);
+}
+
+#[test]
+fn hare_square_previous_round() {
+    let settings = Settings {
+        tie_break: TieBreak::PreviousRound,
+        ..Settings::default()
+    };
+    assert_election_with(
+        "hare_square_previous_round",
+        "hare",
+        "square",
+        128,
+        &settings,
+    );
+}
+
+#[test]
+fn hare_square_borda() {
+    let settings = Settings {
+        tie_break: TieBreak::Borda,
+        ..Settings::default()
+    };
+    assert_election_with("hare_square_borda", "hare", "square", 128, &settings);
+}
+
+#[test]
+fn hare_square_random() {
+    let settings = Settings {
+        tie_break: TieBreak::Random { seed: 7 },
+        ..Settings::default()
+    };
+    assert_election_with("hare_square_random", "hare", "square", 128, &settings);
+}
+
+#[test]
+fn plurality_split_narrow() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance::isotropic(0.1)),
+        num_sigma: 3.0,
+        ..Settings::default()
+    };
+    let name = "plurality_split_sigma_10_3";
+    assert_election_with(name, "plurality", "split", 256, &settings);
+}
+
+#[test]
+fn plurality_split_wide() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance::isotropic(0.5)),
+        num_sigma: 3.0,
+        ..Settings::default()
+    };
+    let name = "plurality_split_sigma_50_3";
+    assert_election_with(name, "plurality", "split", 128, &settings);
+}
+
+#[test]
+fn plurality_split_truncated() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance::isotropic(0.5 / 1.5)),
+        num_sigma: 1.0,
+        ..Settings::default()
+    };
+    let name = "plurality_split_sigma_33_1";
+    assert_election_with(name, "plurality", "split", 256, &settings);
+}
+
+#[test]
+fn hare_nonmonotonic_narrow() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance::isotropic(0.1)),
+        num_sigma: 3.0,
+        ..Settings::default()
+    };
+    let name = "hare_nonmonotonic_sigma_10_3";
+    assert_election_with(name, "hare", "nonmonotonic", 128, &settings);
+}
+
+#[test]
+fn hare_nonmonotonic_wide() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance::isotropic(0.5)),
+        num_sigma: 3.0,
+        ..Settings::default()
+    };
+    let name = "hare_nonmonotonic_sigma_50_3";
+    assert_election_with(name, "hare", "nonmonotonic", 64, &settings);
+}
+
+#[test]
+fn plurality_split_stretched() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance {
+            sigma_x: 0.1,
+            sigma_y: 0.4,
+            correlation: 0.0,
+        }),
+        ..Settings::default()
+    };
+    let name = "plurality_split_covariance_10_40_0";
+    assert_election_with(name, "plurality", "split", 256, &settings);
+}
+
+#[test]
+fn plurality_split_correlated() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance {
+            sigma_x: 0.3,
+            sigma_y: 0.2,
+            correlation: 0.8,
+        }),
+        ..Settings::default()
+    };
+    let name = "plurality_split_covariance_30_20_80";
+    assert_election_with(name, "plurality", "split", 256, &settings);
+}
+
+#[test]
+fn hare_nonmonotonic_anticorrelated() {
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance {
+            sigma_x: 0.25,
+            sigma_y: 0.25,
+            correlation: -0.6,
+        }),
+        ..Settings::default()
+    };
+    let name = "hare_nonmonotonic_covariance_25_25_-60";
+    assert_election_with(name, "hare", "nonmonotonic", 128, &settings);
+}
+
+#[test]
+fn plurality_split_polarized() {
+    let electorate = Electorate::bimodal(Covariance::isotropic(0.15), Point::new(0.5, 0.0));
+    let settings = Settings {
+        electorate: electorate.polarized(1.0),
+        ..Settings::default()
+    };
+    let name = "plurality_split_polarized_50";
+    assert_election_with(name, "plurality", "split", 256, &settings);
+}
+
+#[test]
+fn plurality_split_slightly_polarized() {
+    let electorate = Electorate::bimodal(Covariance::isotropic(0.15), Point::new(0.5, 0.0));
+    let settings = Settings {
+        electorate: electorate.polarized(0.4),
+        ..Settings::default()
+    };
+    let name = "plurality_split_polarized_20";
+    assert_election_with(name, "plurality", "split", 256, &settings);
+}
+
+#[test]
+fn hare_nonmonotonic_polarized() {
+    let electorate = Electorate::bimodal(Covariance::isotropic(0.15), Point::new(0.5, 0.0));
+    let settings = Settings {
+        electorate: electorate.polarized(1.0),
+        ..Settings::default()
+    };
+    let name = "hare_nonmonotonic_polarized_50";
+    assert_election_with(name, "hare", "nonmonotonic", 128, &settings);
+}
+
+#[test]
+fn borda_equilateral_clusters() {
+    let cluster = |weight, x, y| Cluster {
+        weight,
+        offset: Point::new(x, y),
+        shape: Shape::Gaussian,
+        covariance: Covariance::isotropic(0.1),
+    };
+    let settings = Settings {
+        electorate: Electorate {
+            clusters: vec![
+                cluster(2.0, 0.0, 0.0),
+                cluster(1.0, -0.3, 0.2),
+                cluster(1.0, 0.2, 0.3),
+            ],
+        },
+        ..Settings::default()
+    };
+    assert_election_with(
+        "borda_equilateral_clusters",
+        "borda",
+        "equilateral",
+        256,
+        &settings,
+    );
+}
+
+#[test]
+fn approval_split_square() {
+    let settings = Settings {
+        electorate: Electorate::single(Shape::Square, Covariance::isotropic(0.2)),
+        ..Settings::default()
+    };
+    let name = "approval_split_square";
+    assert_election_with(name, "approval", "split", 128, &settings);
+}
+
+#[test]
+fn approval_split_disc() {
+    let settings = Settings {
+        electorate: Electorate::single(Shape::Disc, Covariance::isotropic(0.2)),
+        ..Settings::default()
+    };
+    let name = "approval_split_disc";
+    assert_election_with(name, "approval", "split", 128, &settings);
+}
+
+#[test]
+fn approval_split_laplace() {
+    let settings = Settings {
+        electorate: Electorate::single(Shape::Laplace, Covariance::isotropic(0.2)),
+        ..Settings::default()
+    };
+    let name = "approval_split_laplace";
+    assert_election_with(name, "approval", "split", 128, &settings);
+}
+
+#[test]
+fn approval_split_cauchy() {
+    let settings = Settings {
+        electorate: Electorate::single(Shape::Cauchy, Covariance::isotropic(0.2)),
+        ..Settings::default()
+    };
+    let name = "approval_split_cauchy";
+    assert_election_with(name, "approval", "split", 128, &settings);
+}
+
+#[test]
+fn borda_nonmonotonic_disc() {
+    let settings = Settings {
+        electorate: Electorate::single(Shape::Disc, Covariance::isotropic(0.2)),
+        ..Settings::default()
+    };
+    let name = "borda_nonmonotonic_disc";
+    assert_election_with(name, "borda", "nonmonotonic", 128, &settings);
+}
+
+#[test]
+fn borda_nonmonotonic_cauchy() {
+    let settings = Settings {
+        electorate: Electorate::single(Shape::Cauchy, Covariance::isotropic(0.2)),
+        ..Settings::default()
+    };
+    let name = "borda_nonmonotonic_cauchy";
+    assert_election_with(name, "borda", "nonmonotonic", 128, &settings);
+}
+
+#[test]
+fn plurality_split_margin() {
+    assert_margins("plurality", "split", 256);
+}
+
+#[test]
+fn hare_nonmonotonic_margin() {
+    assert_margins("hare", "nonmonotonic", 128);
+}
+
+#[test]
+fn plurality_shattered_voronoi_euclidean() {
+    // With a narrow electorate, plurality regions are the Voronoi cells of the candidates.
+    let settings = Settings {
+        electorate: Electorate::gaussian(Covariance::isotropic(0.002)),
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_voronoi_euclidean";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn plurality_shattered_voronoi_cityblock() {
+    let settings = Settings {
+        metric: Metric::CityBlock,
+        electorate: Electorate::gaussian(Covariance::isotropic(0.002)),
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_voronoi_cityblock";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn plurality_shattered_voronoi_chebyshev() {
+    let settings = Settings {
+        metric: Metric::Chebyshev,
+        electorate: Electorate::gaussian(Covariance::isotropic(0.002)),
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_voronoi_chebyshev";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn plurality_shattered_cityblock() {
+    let settings = Settings {
+        metric: Metric::CityBlock,
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_cityblock";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn approval_split_chebyshev() {
+    let settings = Settings {
+        metric: Metric::Chebyshev,
+        ..Settings::default()
+    };
+    let name = "approval_split_chebyshev";
+    assert_election_with(name, "approval", "split", 128, &settings);
+}
+
+#[test]
+fn borda_nonmonotonic_minkowski() {
+    let settings = Settings {
+        metric: Metric::Minkowski { p: 4.0 },
+        ..Settings::default()
+    };
+    let name = "borda_nonmonotonic_minkowski";
+    assert_election_with(name, "borda", "nonmonotonic", 128, &settings);
+}
+
+#[test]
+fn hare_square_salience() {
+    let settings = Settings {
+        metric: Metric::Salience { x: 4.0, y: 1.0 },
+        ..Settings::default()
+    };
+    let name = "hare_square_salience";
+    assert_election_with(name, "hare", "square", 128, &settings);
+}
+
+#[test]
+fn plurality_square_slice() {
+    // Candidates spread out along a hidden third issue; voters in the low slice are closest to the
+    // first ones, voters in the high slice to the last ones.
+    let hidden = [0.2, 0.4, 0.6, 0.8];
+    let candidates: Vec<Vec<f32>> = transform(get_candidates("square"))
+        .iter()
+        .zip(hidden)
+        .map(|(c, h)| vec![c.x, c.y, h])
+        .collect();
+    let registry = MethodRegistry::default();
+    let plurality = registry.get("plurality").unwrap();
+    for (center, name) in [(0.3, "low"), (0.7, "high")] {
+        let slice = Slice {
+            hidden: vec![HiddenIssue { center, sigma: 0.2 }],
+            ..Slice::default()
+        };
+        let result =
+            election_in_slice(128, &candidates, &slice, plurality, &Settings::default()).unwrap();
+        let pixels = draw(&result, Shading::Flat, false);
+        let got = image::RgbaImage::from_raw(128, 128, pixels).unwrap();
+        assert_image(
+            &format!("plurality_square_slice_{}", name),
+            &image::DynamicImage::ImageRgba8(got),
+        );
+    }
+}
+
+#[test]
+fn borda_equilateral_slice() {
+    let hidden = [0.5, 0.2, 0.8];
+    let candidates: Vec<Vec<f32>> = transform(get_candidates("equilateral"))
+        .iter()
+        .zip(hidden)
+        .map(|(c, h)| vec![c.x, c.y, h])
+        .collect();
+    let slice = Slice {
+        hidden: vec![HiddenIssue {
+            center: 0.5,
+            sigma: 0.2,
+        }],
+        ..Slice::default()
+    };
+    let registry = MethodRegistry::default();
+    let borda = registry.get("borda").unwrap();
+    let result = election_in_slice(128, &candidates, &slice, borda, &Settings::default()).unwrap();
+    let pixels = draw(&result, Shading::Flat, false);
+    let got = image::RgbaImage::from_raw(128, 128, pixels).unwrap();
+    assert_image(
+        "borda_equilateral_slice",
+        &image::DynamicImage::ImageRgba8(got),
+    );
+}
+
+#[test]
+fn plurality_shattered_voronoi_neutral_valence() {
+    // Neutral valences leave the Voronoi cells as they are.
+    let valence = vec![Valence::default(); 4];
+    let settings = Settings {
+        valence,
+        electorate: Electorate::gaussian(Covariance::isotropic(0.002)),
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_voronoi_euclidean";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn plurality_shattered_voronoi_additive() {
+    let valence = [0.1, 0.0, 0.0, 0.05];
+    let valence = valence.iter().map(|&b| Valence::additive(b)).collect();
+    let settings = Settings {
+        valence,
+        electorate: Electorate::gaussian(Covariance::isotropic(0.002)),
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_voronoi_additive";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn plurality_shattered_voronoi_multiplicative() {
+    let valence = [1.5, 1.0, 1.0, 0.8];
+    let valence = valence
+        .iter()
+        .map(|&q| Valence::multiplicative(q))
+        .collect();
+    let settings = Settings {
+        valence,
+        electorate: Electorate::gaussian(Covariance::isotropic(0.002)),
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_voronoi_multiplicative";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn approval_split_valence() {
+    let bonus = [0.0, 0.1, 0.0];
+    let settings = Settings {
+        valence: bonus.iter().map(|&b| Valence::additive(b)).collect(),
+        ..Settings::default()
+    };
+    let name = "approval_split_valence";
+    assert_election_with(name, "approval", "split", 128, &settings);
+}
+
+#[test]
+fn borda_equilateral_valence() {
+    let bonus = [0.1, 0.0, 0.0];
+    let settings = Settings {
+        valence: bonus.iter().map(|&b| Valence::additive(b)).collect(),
+        ..Settings::default()
+    };
+    let name = "borda_equilateral_valence";
+    assert_election_with(name, "borda", "equilateral", 128, &settings);
+}
+
+#[test]
+fn hare_square_valence() {
+    let bonus = [0.05, 0.0, 0.0, 0.0];
+    let settings = Settings {
+        valence: bonus.iter().map(|&b| Valence::additive(b)).collect(),
+        ..Settings::default()
+    };
+    let name = "hare_square_valence";
+    assert_election_with(name, "hare", "square", 128, &settings);
+}
+
+#[test]
+fn plurality_squeezed_logit() {
+    let settings = Settings {
+        voter_model: VoterModel::Logit {
+            temperature: 0.1,
+            draws: 16,
+        },
+        ..Settings::default()
+    };
+    let name = "plurality_squeezed_logit";
+    assert_election_with(name, "plurality", "squeezed", 128, &settings);
+}
+
+#[test]
+fn hare_nonmonotonic_logit() {
+    let settings = Settings {
+        voter_model: VoterModel::Logit {
+            temperature: 0.05,
+            draws: 16,
+        },
+        ..Settings::default()
+    };
+    let name = "hare_nonmonotonic_logit";
+    assert_election_with(name, "hare", "nonmonotonic", 64, &settings);
+}
+
+#[test]
+fn minimax_shattered_logit() {
+    let settings = Settings {
+        voter_model: VoterModel::Logit {
+            temperature: 0.05,
+            draws: 16,
+        },
+        ..Settings::default()
+    };
+    let name = "minimax_shattered_logit";
+    assert_election_with(name, "minimax", "shattered", 64, &settings);
+}
+
+#[test]
+fn plurality_split_compromise() {
+    let settings = Settings {
+        strategy: Strategy::Compromise,
+        ..Settings::default()
+    };
+    let name = "plurality_split_compromise";
+    assert_election_with(name, "plurality", "split", 128, &settings);
+}
+
+#[test]
+fn borda_nonmonotonic_bury() {
+    let settings = Settings {
+        strategy: Strategy::Bury,
+        ..Settings::default()
+    };
+    let name = "borda_nonmonotonic_bury";
+    assert_election_with(name, "borda", "nonmonotonic", 128, &settings);
+}
+
+#[test]
+fn approval_square_frontrunner() {
+    let settings = Settings {
+        strategy: Strategy::ApproveFrontrunner,
+        ..Settings::default()
+    };
+    let name = "approval_square_frontrunner";
+    assert_election_with(name, "approval", "square", 128, &settings);
+}
+
+#[test]
+fn hare_nonmonotonic_pushover() {
+    let settings = Settings {
+        strategy: Strategy::Pushover,
+        strategic_share: 0.3,
+        ..Settings::default()
+    };
+    let name = "hare_nonmonotonic_pushover";
+    assert_election_with(name, "hare", "nonmonotonic", 64, &settings);
+}
+
+#[test]
+fn hare_ten_top2() {
+    let settings = Settings {
+        truncation: Truncation::TopK(2),
+        ..Settings::default()
+    };
+    let name = "hare_ten_top2";
+    assert_election_with(name, "hare", "ten", 64, &settings);
+}
+
+#[test]
+fn minimax_shattered_top2() {
+    let settings = Settings {
+        truncation: Truncation::TopK(2),
+        ..Settings::default()
+    };
+    let name = "minimax_shattered_top2";
+    assert_election_with(name, "minimax", "shattered", 128, &settings);
+}
+
+#[test]
+fn hare_square_radius() {
+    let candidates = transform(get_candidates("square"));
+    let settings = Settings {
+        truncation: Truncation::Radius(0.4),
+        ..Settings::default()
+    };
+    let registry = MethodRegistry::default();
+    let hare = registry.get("hare").unwrap();
+    let result = election(128, &candidates, hare, &settings).unwrap();
+    let pixels = draw(&result, Shading::Exhausted, false);
+    let got = image::RgbaImage::from_raw(128, 128, pixels).unwrap();
+    assert_image(
+        "hare_square_radius_exhausted",
+        &image::DynamicImage::ImageRgba8(got),
+    );
+}
+
+#[test]
+fn plurality_shattered_alienation() {
+    let settings = Settings {
+        turnout: Turnout {
+            alienation: Some(0.2),
+            indifference: None,
+        },
+        ..Settings::default()
+    };
+    let name = "plurality_shattered_alienation";
+    assert_election_with(name, "plurality", "shattered", 128, &settings);
+}
+
+#[test]
+fn borda_equilateral_turnout() {
+    let candidates = transform(get_candidates("equilateral"));
+    let settings = Settings {
+        turnout: Turnout {
+            alienation: Some(0.3),
+            indifference: Some(0.05),
+        },
+        ..Settings::default()
+    };
+    let registry = MethodRegistry::default();
+    let borda = registry.get("borda").unwrap();
+    let result = election(128, &candidates, borda, &settings).unwrap();
+    let pixels = draw(&result, Shading::Turnout, false);
+    let got = image::RgbaImage::from_raw(128, 128, pixels).unwrap();
+    assert_image(
+        "borda_equilateral_turnout",
+        &image::DynamicImage::ImageRgba8(got),
+    );
+}
+
+#[test]
+fn plurality_split_sampled() {
+    let candidates = transform(get_candidates("split"));
+    let sampling = Sampling {
+        num_voters: 200,
+        repeats: 16,
+        seed: 0,
+    };
+    let registry = MethodRegistry::default();
+    let plurality = registry.get("plurality").unwrap();
+    let settings = Settings::default();
+    let result = sample_election(128, &candidates, plurality, &settings, &sampling).unwrap();
+    let pixels = draw_sampled(&result);
+    let got = image::RgbaImage::from_raw(128, 128, pixels).unwrap();
+    assert_image(
+        "plurality_split_sampled",
+        &image::DynamicImage::ImageRgba8(got),
+    );
+}
+
+#[test]
+fn hare_nonmonotonic_exact() {
+    let settings = Settings {
+        integration: Integration::Exact,
+        ..Settings::default()
+    };
+    let name = "hare_nonmonotonic_exact";
+    assert_election_with(name, "hare", "nonmonotonic", 128, &settings);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{
+    election, election_in_slice, explain, explain_in_slice, Borda, HiddenIssue, Plurality,
+    Settings, Slice,
+};
+
+/// The candidates, at the same position `hidden` along a third issue between their two others.
+fn with_hidden(hidden: &[f32]) -> Vec<Vec<f32>> {
+    get_candidates("kite")
+        .iter()
+        .zip(hidden)
+        .map(|(c, &h)| vec![c.x, h, c.y])
+        .collect()
+}
+
+fn max_difference(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f32::max)
+}
+
+#[test]
+fn plane_matches_two_dimensions() {
+    let settings = Settings::default();
+    let positions: Vec<Vec<f32>> = get_candidates("kite")
+        .iter()
+        .map(|c| vec![c.x, c.y])
+        .collect();
+    let flat = election(24, &get_candidates("kite"), &Borda, &settings).unwrap();
+    let sliced = election_in_slice(24, &positions, &Slice::default(), &Borda, &settings).unwrap();
+    assert_eq!(flat.shares(), sliced.shares());
+
+    // Swapping the axes transposes the map, up to the rounding of the separable kernel.
+    let swapped = Slice {
+        axes: (1, 0),
+        ..Slice::default()
+    };
+    let transposed = election_in_slice(24, &positions, &swapped, &Borda, &settings).unwrap();
+    for x in 0..24 {
+        for y in 0..24 {
+            let difference = max_difference(flat.shares_at(x, y), transposed.shares_at(y, x));
+            assert!(
+                difference < 1e-3,
+                "({}, {}) differs by {}",
+                x,
+                y,
+                difference
+            );
+        }
+    }
+}
+
+#[test]
+fn shared_hidden_position_changes_nothing() {
+    let settings = Settings::default();
+    let flat = election(24, &get_candidates("kite"), &Borda, &settings).unwrap();
+    let slice = Slice::through((0, 2), &[0.0, 0.3, 0.0], 0.2);
+    let sliced = election_in_slice(24, &with_hidden(&[0.5; 4]), &slice, &Borda, &settings);
+    let difference = max_difference(flat.shares(), sliced.unwrap().shares());
+    assert!(difference < 1e-5, "differs by {}", difference);
+}
+
+#[test]
+fn hidden_issue_favors_nearby_candidates() {
+    let settings = Settings::default();
+    let candidates = with_hidden(&[0.0, 0.0, 1.0, 1.0]);
+    let share = |center| {
+        let slice = Slice {
+            axes: (0, 2),
+            hidden: vec![HiddenIssue { center, sigma: 0.2 }],
+            ..Slice::default()
+        };
+        let result = election_in_slice(24, &candidates, &slice, &Plurality, &settings).unwrap();
+        let shares = result.shares();
+        shares.chunks(4).map(|s| s[0] + s[1]).sum::<f32>() / (24 * 24) as f32
+    };
+    assert!(share(0.0) > 0.9);
+    assert!(share(0.5) > 0.3 && share(0.5) < 0.7);
+    assert!(share(1.0) < 0.1);
+    // The hidden issue is integrated symmetrically around its center.
+    assert!((share(0.25) + share(0.75) - 1.0).abs() < 0.05);
+}
+
+#[test]
+fn explanation_matches_election() {
+    let settings = Settings::default();
+    let candidates = with_hidden(&[0.2, 0.8, 0.5, 0.1]);
+    let slice = Slice::through((0, 2), &[0.0, 0.4, 0.0], 0.15);
+    let result = election_in_slice(16, &candidates, &slice, &Borda, &settings).unwrap();
+    for &(x, y) in [(3, 4), (12, 7), (8, 15)].iter() {
+        let explanation =
+            explain_in_slice(16, x, y, &candidates, &slice, &Borda, &settings).unwrap();
+        assert_eq!(
+            explanation.outcome,
+            result.outcome_at(x as usize, y as usize)
+        );
+    }
+
+    // Without hidden issues, explanations in a slice are the same as in the plane.
+    let plane: Vec<Vec<f32>> = self::get_candidates("kite")
+        .iter()
+        .map(|c| vec![c.x, c.y])
+        .collect();
+    assert_eq!(
+        explain_in_slice(16, 5, 9, &plane, &Slice::default(), &Borda, &settings),
+        explain(16, 5, 9, &self::get_candidates("kite"), &Borda, &settings)
+    );
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{
+    election, explain, Approval, Borda, ElectionMethod, Hare, Plurality, Point, Settings, Strategy,
+};
+
+/// The winner and runner-up of the sincere election at each point.
+fn polls(method: &dyn ElectionMethod) -> Vec<(usize, usize)> {
+    let sincere = election(24, &get_candidates("kite"), method, &Settings::default()).unwrap();
+    let last = sincere.rounds.last().unwrap();
+    sincere
+        .outcomes
+        .iter()
+        .zip(last.chunks(get_candidates("kite").len()))
+        .map(|(outcome, shares)| {
+            let runner_up = (0..shares.len())
+                .filter(|&c| c != outcome.winner)
+                .fold(None, |best: Option<usize>, c| match best {
+                    Some(b) if shares[b] >= shares[c] => Some(b),
+                    _ => Some(c),
+                })
+                .unwrap();
+            (outcome.winner, runner_up)
+        })
+        .collect()
+}
+
+#[test]
+fn no_strategic_voters_is_sincere() {
+    for &(method, strategy) in [
+        (&Plurality as &dyn ElectionMethod, Strategy::Compromise),
+        (&Borda, Strategy::Bury),
+        (&Approval::default(), Strategy::ApproveFrontrunner),
+        (&Hare, Strategy::Pushover),
+    ]
+    .iter()
+    {
+        let sincere = election(24, &get_candidates("kite"), method, &Settings::default()).unwrap();
+        let settings = Settings {
+            strategy,
+            strategic_share: 0.0,
+            ..Settings::default()
+        };
+        let none = election(24, &get_candidates("kite"), method, &settings).unwrap();
+        assert_eq!(sincere.outcomes, none.outcomes, "{:?}", strategy);
+        assert_eq!(sincere.rounds, none.rounds, "{:?}", strategy);
+    }
+}
+
+#[test]
+fn compromising_voters_only_vote_for_frontrunners() {
+    let settings = Settings {
+        strategy: Strategy::Compromise,
+        ..Settings::default()
+    };
+    let result = election(24, &get_candidates("kite"), &Plurality, &settings).unwrap();
+    for ((frontrunner, runner_up), shares) in
+        polls(&Plurality).into_iter().zip(result.shares().chunks(4))
+    {
+        let others: f32 = (0..4)
+            .filter(|&c| c != frontrunner && c != runner_up)
+            .map(|c| shares[c])
+            .sum();
+        assert_eq!(others, 0.0);
+    }
+}
+
+#[test]
+fn buried_ballots_give_at_most_full_borda_points() {
+    // Voters beside the line between the last two candidates rank them equally, behind the
+    // first one. Burying either of them mustn't rank it below the last rank.
+    let candidates = vec![
+        Point::new(0.5, 0.2),
+        Point::new(0.25, 0.7),
+        Point::new(0.75, 0.7),
+    ];
+    let settings = Settings {
+        strategy: Strategy::Bury,
+        ..Settings::default()
+    };
+    let result = election(32, &candidates, &Borda, &settings).unwrap();
+    // The favourite of a ballot gets 2 of its 3 Borda points.
+    for share in result.shares() {
+        assert!(*share <= 2.0 / 3.0 + 1e-6, "{}", share);
+    }
+}
+
+#[test]
+fn explanation_matches_election() {
+    for &(method, strategy) in [
+        (&Plurality as &dyn ElectionMethod, Strategy::Compromise),
+        (&Borda, Strategy::Bury),
+        (&Hare, Strategy::Pushover),
+    ]
+    .iter()
+    {
+        let settings = Settings {
+            strategy,
+            strategic_share: 0.6,
+            ..Settings::default()
+        };
+        let result = election(24, &get_candidates("kite"), method, &settings).unwrap();
+        for &(x, y) in [(3, 4), (12, 7), (20, 20), (8, 15)].iter() {
+            let explanation =
+                explain(24, x, y, &get_candidates("kite"), method, &settings).unwrap();
+            assert_eq!(
+                explanation.outcome,
+                result.outcome_at(x as usize, y as usize),
+                "{:?} at ({}, {})",
+                strategy,
+                x,
+                y
+            );
+        }
+    }
+}
+
+#[test]
+fn parse_strategy() {
+    assert_eq!("compromise".parse(), Ok(Strategy::Compromise));
+    assert_eq!("frontrunner".parse(), Ok(Strategy::ApproveFrontrunner));
+    assert!("bullet".parse::<Strategy>().is_err());
+}
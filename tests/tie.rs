@@ -0,0 +1,47 @@
+use voting_map::{
+    election, explain, Cluster, Covariance, Electorate, Hare, Point, Settings, Shape, TieBreak,
+};
+
+/// Three tight clusters, each casting a single ranking: A > C > B, B > A > C and, one and a half
+/// times as large, C > A > B. A and B tie for the fewest first choices, but A has more Borda
+/// points.
+fn tied_for_elimination() -> (Vec<Point>, Settings) {
+    let candidates = vec![
+        Point::new(0.2, 0.5),
+        Point::new(0.8, 0.5),
+        Point::new(0.5, 0.9),
+    ];
+    let cluster = |weight, x, y| Cluster {
+        weight,
+        offset: Point::new(x, y),
+        shape: Shape::Disc,
+        covariance: Covariance::isotropic(0.02),
+    };
+    let settings = Settings {
+        electorate: Electorate {
+            clusters: vec![
+                cluster(1.0, -0.25, 0.1),
+                cluster(1.0, 0.2, -0.1),
+                cluster(1.5, -0.05, 0.35),
+            ],
+        },
+        tie_break: TieBreak::Borda,
+        ..Settings::default()
+    };
+    (candidates, settings)
+}
+
+#[test]
+fn borda_eliminates_the_fewest_points() {
+    let (candidates, settings) = tied_for_elimination();
+    let explanation = explain(64, 32, 32, &candidates, &Hare, &settings).unwrap();
+    let shares = &explanation.rounds[0].shares;
+    assert!((shares[0] - shares[1]).abs() < 1e-4, "{:?}", shares);
+    // B has the fewest Borda points, so it goes, and its voters carry A past C.
+    assert_eq!(explanation.rounds[0].eliminated, Some(1));
+    assert_eq!(explanation.outcome.winner, 0);
+    assert!(explanation.outcome.tied);
+
+    let result = election(64, &candidates, &Hare, &settings).unwrap();
+    assert_eq!(result.outcome_at(32, 32), explanation.outcome);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{election, explain, Borda, Hare, Minimax, Plurality, Settings, Truncation};
+
+#[test]
+fn full_rankings_are_never_exhausted() {
+    let full = election(
+        32,
+        &get_candidates("skewed_kite"),
+        &Hare,
+        &Settings::default(),
+    )
+    .unwrap();
+    assert!(full.exhausted.iter().all(|&e| e == 0.0));
+
+    // Ranking every candidate, or every candidate in reach, is the same as a full ranking.
+    for truncation in [Truncation::TopK(4), Truncation::Radius(10.0)] {
+        let settings = Settings {
+            truncation,
+            ..Settings::default()
+        };
+        let result = election(32, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+        assert_eq!(result.rounds, full.rounds);
+        assert_eq!(result.outcomes, full.outcomes);
+        assert!(result.exhausted.iter().all(|&e| e == 0.0));
+    }
+}
+
+#[test]
+fn bullet_votes_are_plurality() {
+    let settings = Settings {
+        truncation: Truncation::TopK(1),
+        ..Settings::default()
+    };
+    let plurality = election(32, &get_candidates("skewed_kite"), &Plurality, &settings).unwrap();
+
+    // Borda gives the only ranked candidate the same points on every ballot.
+    let borda = election(32, &get_candidates("skewed_kite"), &Borda, &settings).unwrap();
+    for (a, b) in borda.shares().iter().zip(plurality.shares()) {
+        assert!((a - b).abs() < 1e-5, "{} vs {}", a, b);
+    }
+
+    // Votes never transfer, so the instant runoff leader keeps leading, while the ballots of
+    // eliminated candidates run out.
+    let hare = election(32, &get_candidates("skewed_kite"), &Hare, &settings).unwrap();
+    for (point, outcome) in hare.outcomes.iter().enumerate() {
+        if !outcome.tied && !plurality.outcomes[point].tied {
+            assert_eq!(outcome.winner, plurality.outcomes[point].winner);
+        }
+        let last = hare.rounds.last().unwrap();
+        let winner_share = last[point * 4 + outcome.winner];
+        assert!(winner_share >= 0.5);
+        if hare.num_rounds[point] > 1 {
+            assert!(hare.exhausted[point] > 0.0);
+            let first = plurality.shares()[point * 4 + outcome.winner];
+            assert!((first - winner_share * (1.0 - hare.exhausted[point])).abs() < 1e-4);
+        } else {
+            assert_eq!(hare.exhausted[point], 0.0);
+        }
+    }
+}
+
+#[test]
+fn voters_out_of_reach_abstain() {
+    let settings = Settings {
+        truncation: Truncation::Radius(0.3),
+        ..Settings::default()
+    };
+    let result = election(32, &get_candidates("skewed_kite"), &Plurality, &settings).unwrap();
+    assert!(result.exhausted.iter().all(|&e| (0.0..=1.0).contains(&e)));
+    // Voters around a candidate have someone to vote for, more than voters in a corner.
+    let near = result.exhausted[(0.5 * 32.0) as usize * 32 + (0.55 * 32.0) as usize];
+    let far = result.exhausted[32 * 32 - 1];
+    assert!(near < far, "{} vs {}", near, far);
+
+    // Pairwise contests only count voters who rank one candidate above the other.
+    let explanation = explain(
+        32,
+        16,
+        17,
+        &get_candidates("skewed_kite"),
+        &Minimax,
+        &settings,
+    )
+    .unwrap();
+    let pairwise = explanation.pairwise.unwrap();
+    for (a, row) in pairwise.iter().enumerate() {
+        for (b, &support) in row.iter().enumerate() {
+            if a != b {
+                assert!((support + pairwise[b][a] - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_truncation() {
+    assert_eq!("full".parse(), Ok(Truncation::Full));
+    assert_eq!("top:2".parse(), Ok(Truncation::TopK(2)));
+    assert_eq!("radius:0.5".parse(), Ok(Truncation::Radius(0.5)));
+    assert!("top".parse::<Truncation>().is_err());
+    assert!("radius:far".parse::<Truncation>().is_err());
+}
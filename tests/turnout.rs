@@ -0,0 +1,161 @@
+mod common;
+
+use common::get_candidates;
+use voting_map::{
+    election, explain, Approval, Borda, Covariance, Electorate, Hare, Plurality, Point, Settings,
+    Turnout,
+};
+
+#[test]
+fn everybody_votes_by_default() {
+    let full = election(
+        32,
+        &get_candidates("skewed_triangle"),
+        &Borda,
+        &Settings::default(),
+    )
+    .unwrap();
+    assert!(full.turnout.iter().all(|&t| t == 1.0));
+
+    // Voters who barely care about distance all turn out.
+    let turnout = Turnout {
+        alienation: Some(1e9),
+        indifference: None,
+    };
+    let settings = Settings {
+        turnout,
+        ..Settings::default()
+    };
+    let result = election(32, &get_candidates("skewed_triangle"), &Borda, &settings).unwrap();
+    assert!(result.turnout.iter().all(|&t| (t - 1.0).abs() < 1e-4));
+    for (a, b) in result.shares().iter().zip(full.shares()) {
+        assert!((a - b).abs() < 1e-4, "{} vs {}", a, b);
+    }
+}
+
+#[test]
+fn alienated_voters_stay_home() {
+    let alienation = 0.2;
+    let settings = Settings {
+        turnout: Turnout {
+            alienation: Some(alienation),
+            indifference: None,
+        },
+        electorate: Electorate::gaussian(Covariance::isotropic(0.01)),
+        ..Settings::default()
+    };
+    let result = election(
+        32,
+        &get_candidates("skewed_triangle"),
+        &Plurality,
+        &settings,
+    )
+    .unwrap();
+    // Around a point, nearly all voters are as far from their closest candidate as the point.
+    for &(x, y) in [(16, 17), (0, 0), (31, 31), (8, 20)].iter() {
+        let at = Point::new(x as f32 / 32.0, y as f32 / 32.0);
+        let closest = get_candidates("skewed_triangle")
+            .iter()
+            .map(|c| ((c.x - at.x).powi(2) + (c.y - at.y).powi(2)).sqrt())
+            .fold(f32::INFINITY, f32::min);
+        let expected = 0.5f32.powf(closest / alienation);
+        let turnout = result.turnout[x * 32 + y];
+        assert!(
+            (turnout - expected).abs() < 0.02,
+            "{} vs {}",
+            turnout,
+            expected
+        );
+    }
+}
+
+#[test]
+fn indifferent_voters_stay_home() {
+    let turnout = Turnout {
+        alienation: None,
+        indifference: Some(0.1),
+    };
+    let settings = Settings {
+        turnout,
+        ..Settings::default()
+    };
+    let result = election(
+        32,
+        &get_candidates("skewed_triangle"),
+        &Plurality,
+        &settings,
+    )
+    .unwrap();
+    assert!(result.turnout.iter().all(|&t| t > 0.0 && t < 1.0));
+    // Voters halfway between two candidates care less than voters next to one of them.
+    let near = result.turnout[(0.2 * 32.0) as usize * 32 + (0.3 * 32.0) as usize];
+    let between = result.turnout[(0.35 * 32.0) as usize * 32 + (0.42 * 32.0) as usize];
+    assert!(between < near, "{} vs {}", between, near);
+}
+
+#[test]
+fn every_method_sees_the_same_voters() {
+    let settings = Settings {
+        turnout: Turnout {
+            alienation: Some(0.3),
+            indifference: Some(0.05),
+        },
+        ..Settings::default()
+    };
+    let plurality = election(
+        32,
+        &get_candidates("skewed_triangle"),
+        &Plurality,
+        &settings,
+    )
+    .unwrap();
+    let hare = election(32, &get_candidates("skewed_triangle"), &Hare, &settings).unwrap();
+    let approval = election(
+        32,
+        &get_candidates("skewed_triangle"),
+        &Approval::default(),
+        &settings,
+    )
+    .unwrap();
+    assert_eq!(plurality.turnout, hare.turnout);
+    assert_eq!(plurality.turnout, approval.turnout);
+
+    // The first round of an instant runoff is a plurality vote of the voters who turned out.
+    for (a, b) in hare.shares().iter().zip(plurality.shares()) {
+        assert!((a - b).abs() < 1e-5, "{} vs {}", a, b);
+    }
+    let explanation = explain(
+        32,
+        10,
+        20,
+        &get_candidates("skewed_triangle"),
+        &Hare,
+        &settings,
+    )
+    .unwrap();
+    let shares = plurality.shares_at(10, 20);
+    for (a, b) in explanation.rounds[0].shares.iter().zip(shares) {
+        assert!((a - b).abs() < 1e-5, "{} vs {}", a, b);
+    }
+}
+
+#[test]
+fn parse_turnout() {
+    assert_eq!("full".parse(), Ok(Turnout::default()));
+    assert_eq!(
+        "alienation:0.3".parse(),
+        Ok(Turnout {
+            alienation: Some(0.3),
+            indifference: None
+        })
+    );
+    assert_eq!(
+        "alienation:0.3,indifference:0.05".parse(),
+        Ok(Turnout {
+            alienation: Some(0.3),
+            indifference: Some(0.05)
+        })
+    );
+    assert!("apathy:0.3".parse::<Turnout>().is_err());
+    assert!("alienation".parse::<Turnout>().is_err());
+}